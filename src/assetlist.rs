@@ -0,0 +1,201 @@
+//! Contains models for serializing and deserializing the `assetlist.json` in a given chain's directory in the registry repository
+use serde::{Deserialize, Serialize};
+
+/// The assets of a chain.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct AssetList {
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub chain_name: String,
+    pub assets: Vec<Asset>,
+}
+
+impl AssetList {
+    /// Get an asset by its base denom, e.g. `uatom` or `ibc/27394FB...`.
+    pub fn get_by_base(&self, base_denom: &str) -> Option<&Asset> {
+        self.assets.iter().find(|asset| asset.base == base_denom)
+    }
+
+    /// Get an asset by its symbol, ignoring case, e.g. `ATOM`.
+    pub fn get_by_symbol(&self, symbol: &str) -> Option<&Asset> {
+        self.assets
+            .iter()
+            .find(|asset| asset.symbol.eq_ignore_ascii_case(symbol))
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Asset {
+    pub description: String,
+    pub denom_units: Vec<DenomUnit>,
+    pub base: String,
+    pub name: String,
+    pub display: String,
+    pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_asset: Option<String>,
+    /// The contract address of a cw20, erc20 or snip20 token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(rename = "logo_URIs", skip_serializing_if = "Option::is_none")]
+    pub logo_uris: Option<LogoUris>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
+    pub images: Vec<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coingecko_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
+    pub keywords: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
+    pub traces: Vec<Trace>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ibc: Option<AssetIbc>,
+}
+
+impl Asset {
+    /// The denom unit named by `display`, e.g. `atom` for `uatom`.
+    pub fn display_unit(&self) -> Option<&DenomUnit> {
+        self.denom_units
+            .iter()
+            .find(|unit| unit.denom == self.display)
+    }
+
+    /// The number of decimals of the display denom relative to the base denom, `0` if the
+    /// display denom is not listed in `denom_units`.
+    pub fn decimals(&self) -> u32 {
+        self.display_unit().map(|unit| unit.exponent).unwrap_or(0)
+    }
+
+    /// Formats an amount of the base denom in the display denom without losing precision,
+    /// e.g. `1500000` uatom as `1.5`.
+    pub fn to_display_amount(&self, amount: u128) -> String {
+        let decimals = self.decimals() as usize;
+        if decimals == 0 {
+            return amount.to_string();
+        }
+
+        let digits = format!("{:0>width$}", amount, width = decimals + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - decimals);
+        let fraction = fraction.trim_end_matches('0');
+
+        if fraction.is_empty() {
+            whole.to_string()
+        } else {
+            format!("{}.{}", whole, fraction)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct DenomUnit {
+    pub denom: String,
+    pub exponent: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
+    pub aliases: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct LogoUris {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub png: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub svg: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jpeg: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Image {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub png: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub svg: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jpeg: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<serde_json::Value>,
+}
+
+/// Where an asset originates from, e.g. the IBC hop it was transferred over.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Trace {
+    /// The kind of trace, e.g. `ibc`, `ibc-cw20`, `bridge`, `wrapped` or `liquid-stake`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub counterparty: TraceCounterparty,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain: Option<TraceChain>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+}
+
+/// The origin side of a [`Trace`].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct TraceCounterparty {
+    pub chain_name: String,
+    pub base_denom: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<String>,
+}
+
+/// The receiving side of a [`Trace`].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct TraceChain {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<String>,
+    /// The full denom trace, e.g. `transfer/channel-0/uatom`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// The legacy IBC information of an asset, superseded by [`Trace`].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct AssetIbc {
+    pub source_channel: String,
+    pub dst_channel: String,
+    pub source_denom: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_display_amounts() {
+        let asset = Asset {
+            base: "uatom".to_string(),
+            display: "atom".to_string(),
+            denom_units: vec![
+                DenomUnit {
+                    denom: "uatom".to_string(),
+                    exponent: 0,
+                    ..Default::default()
+                },
+                DenomUnit {
+                    denom: "atom".to_string(),
+                    exponent: 6,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(asset.decimals(), 6);
+        assert_eq!(asset.to_display_amount(1_500_000), "1.5");
+        assert_eq!(asset.to_display_amount(2_000_000), "2");
+        assert_eq!(asset.to_display_amount(25), "0.000025");
+        assert_eq!(asset.to_display_amount(0), "0");
+    }
+}
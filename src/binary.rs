@@ -0,0 +1,203 @@
+//! Contains the selection of a chain's binary for the current platform and its verified
+//! download.
+use crate::chain::{current_platform, Binaries};
+use crate::fetch::{sha256_hex, split_checksum, Checksum, Fetcher};
+use crate::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A binary listed in `codebase.binaries`, with the checksum embedded in its url split off.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinaryUrl {
+    /// The platform, e.g. `linux/amd64`.
+    pub platform: String,
+    /// The url without the `checksum` query parameter.
+    pub url: String,
+    pub checksum: Option<Checksum>,
+}
+
+impl BinaryUrl {
+    /// Parses a url as listed in the registry, e.g. `https://host/junod?checksum=sha256:...`.
+    pub fn parse(platform: &str, url: &str) -> Self {
+        let (url, checksum) = split_checksum(url);
+        Self {
+            platform: platform.to_string(),
+            url,
+            checksum,
+        }
+    }
+}
+
+/// A binary written to disk by [`download`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DownloadedBinary {
+    pub path: PathBuf,
+    /// The lower case hex sha256 of the binary.
+    pub sha256: String,
+    /// `true` if the registry published a checksum for the binary, which it matched.
+    pub verified: bool,
+}
+
+/// Selects the binary for the platform this crate was compiled for.
+/// Returns [`Error::NotFound`] if the registry lists no binary for it.
+pub fn select(binaries: &Binaries) -> Result<BinaryUrl, Error> {
+    let platform =
+        current_platform().ok_or_else(|| Error::NotFound("binary for this platform".into()))?;
+    select_for(binaries, platform)
+}
+
+/// Selects the binary for a platform such as `linux/arm64`.
+/// Returns [`Error::NotFound`] if the registry lists no binary for it.
+pub fn select_for(binaries: &Binaries, platform: &str) -> Result<BinaryUrl, Error> {
+    binaries
+        .get(platform)
+        .map(|url| BinaryUrl::parse(platform, url))
+        .ok_or_else(|| Error::NotFound(format!("binary for {}", platform)))
+}
+
+/// Downloads a binary and writes it as an executable to `path`, after checking it against the
+/// checksum the registry published for it. Archives are written as-is.
+///
+/// Returns [`Error::MissingChecksum`] without downloading anything if the registry published no
+/// checksum, see [`download_unverified`], and [`Error::ChecksumMismatch`] without writing
+/// anything if the download does not match.
+///
+/// ## Example
+///
+/// ```no_run
+/// use cosmos_chain_registry::{binary, ChainRegistry};
+///
+/// let registry = ChainRegistry::from_path("./chain-registry").unwrap();
+/// let info = registry.get_by_chain_name("juno").unwrap();
+/// let junod = binary::select(&info.codebase.binaries).unwrap();
+/// # let fetcher = |_: &str| -> Result<Vec<u8>, cosmos_chain_registry::Error> { Ok(Vec::new()) };
+///
+/// // e.g. a `fetch::HttpFetcher` with the `http` feature
+/// binary::download(&junod, &fetcher, "bin/junod").unwrap();
+/// ```
+pub fn download(
+    binary: &BinaryUrl,
+    fetcher: &impl Fetcher,
+    path: impl AsRef<Path>,
+) -> Result<DownloadedBinary, Error> {
+    if binary.checksum.is_none() {
+        return Err(Error::MissingChecksum {
+            url: binary.url.clone(),
+        });
+    }
+    download_unverified(binary, fetcher, path)
+}
+
+/// Like [`download`], but also writes binaries the registry published no checksum for. Their
+/// [`DownloadedBinary::verified`] is `false`, the caller is responsible for trusting them.
+pub fn download_unverified(
+    binary: &BinaryUrl,
+    fetcher: &impl Fetcher,
+    path: impl AsRef<Path>,
+) -> Result<DownloadedBinary, Error> {
+    let path = path.as_ref();
+    let bytes = fetcher.fetch(&binary.url)?;
+    if let Some(checksum) = &binary.checksum {
+        checksum.verify(&binary.url, &bytes)?;
+    }
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+    }
+
+    // Write next to the target first so an interrupted download never leaves a partial binary
+    let partial = path.with_file_name(format!(
+        "{}.part",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    fs::write(&partial, &bytes).map_err(|e| Error::io(&partial, e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&partial, fs::Permissions::from_mode(0o755))
+            .map_err(|e| Error::io(&partial, e))?;
+    }
+    fs::rename(&partial, path).map_err(|e| Error::io(path, e))?;
+
+    Ok(DownloadedBinary {
+        path: path.to_path_buf(),
+        sha256: sha256_hex(&bytes),
+        verified: binary.checksum.is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downloads_and_verifies_binaries() {
+        let contents = b"#!/bin/sh\necho junod\n".to_vec();
+        let checksum = sha256_hex(&contents);
+        let binaries = Binaries {
+            linux_amd_64: format!("https://host/junod?checksum=sha256:{}", checksum),
+            linux_arm_64: "https://host/junod-arm64?checksum=sha256:00".to_string(),
+            darwin_arm_64: "https://host/junod-darwin".to_string(),
+            ..Default::default()
+        };
+        let fetcher = |url: &str| -> Result<Vec<u8>, Error> {
+            match url {
+                "https://host/junod" | "https://host/junod-arm64" | "https://host/junod-darwin" => {
+                    Ok(contents.clone())
+                }
+                _ => Err(Error::Fetch {
+                    url: url.to_string(),
+                    reason: "404".to_string(),
+                }),
+            }
+        };
+
+        let junod = select_for(&binaries, "linux/amd64").unwrap();
+        assert_eq!(junod.url, "https://host/junod");
+        assert_eq!(junod.checksum, Some(Checksum::sha256(&checksum)));
+        assert!(matches!(
+            select_for(&binaries, "windows/amd64"),
+            Err(Error::NotFound(_))
+        ));
+        assert_eq!(
+            select(&binaries).ok().map(|binary| binary.platform),
+            current_platform()
+                .filter(|platform| binaries.get(platform).is_some())
+                .map(str::to_string)
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bin/junod");
+        let downloaded = download(&junod, &fetcher, &path).unwrap();
+        assert!(downloaded.verified);
+        assert_eq!(downloaded.sha256, checksum);
+        assert_eq!(fs::read(&path).unwrap(), contents);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+
+        // A mismatching download is never written
+        let path = dir.path().join("junod-arm64");
+        let arm = select_for(&binaries, "linux/arm64").unwrap();
+        assert!(matches!(
+            download(&arm, &fetcher, &path),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(!path.exists());
+
+        // Binaries without a checksum are only written when explicitly allowed
+        let darwin = select_for(&binaries, "darwin/arm64").unwrap();
+        let path = dir.path().join("junod-darwin");
+        assert!(matches!(
+            download(&darwin, &fetcher, &path),
+            Err(Error::MissingChecksum { .. })
+        ));
+        assert!(!path.exists());
+        let downloaded = download_unverified(&darwin, &fetcher, &path).unwrap();
+        assert!(!downloaded.verified);
+        assert!(path.exists());
+    }
+}
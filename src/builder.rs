@@ -0,0 +1,434 @@
+//! Contains the [`ChainRegistryBuilder`] used to configure where a [`ChainRegistry`] is fetched
+//! from, where it is cached on disk and when it is refreshed.
+use crate::{ChainRegistry, Error};
+use fs2::FileExt;
+use git2::{build::RepoBuilder, Cred, FetchOptions, RemoteCallbacks, Repository};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+/// The git url of the upstream [Cosmos Chain Registry](https://github.com/cosmos/chain-registry).
+pub const DEFAULT_CHAIN_REGISTRY_URL: &str = "https://github.com/cosmos/chain-registry";
+
+/// The git ref of the upstream registry that is checked out by default.
+pub const DEFAULT_CHAIN_REGISTRY_REF: &str = "master";
+
+/// Controls when the local clone of the registry is updated from the remote.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FetchPolicy {
+    /// Clone the registry if it is missing, otherwise fetch and checkout the configured ref.
+    #[default]
+    Always,
+    /// Clone the registry if it is missing, otherwise use the existing clone as-is.
+    IfMissing,
+    /// Never touch the network, the cache directory must already contain the registry.
+    Never,
+}
+
+/// Credentials used when cloning or fetching from a private registry fork.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GitAuth {
+    /// Plain username and password authentication over https.
+    UserPass { username: String, password: String },
+    /// A personal access token, sent as the password for https remotes.
+    Token(String),
+    /// Authenticate over ssh using the keys loaded in the running ssh agent.
+    SshAgent { username: String },
+}
+
+/// Builds a [`ChainRegistry`] from an explicit remote, ref and cache directory.
+///
+/// Each builder is independent, so registries backed by different forks or refs can be used
+/// side by side in the same process.
+///
+/// ## Example
+///
+/// ```no_run
+/// use cosmos_chain_registry::{ChainRegistryBuilder, FetchPolicy};
+///
+/// let registry = ChainRegistryBuilder::new()
+///     .url("https://github.com/cosmos/chain-registry")
+///     .git_ref("master")
+///     .cache_dir("/tmp/chain-registry")
+///     .fetch_policy(FetchPolicy::IfMissing)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ChainRegistryBuilder {
+    url: String,
+    git_ref: String,
+    cache_dir: Option<PathBuf>,
+    fetch_policy: FetchPolicy,
+    auth: Option<GitAuth>,
+}
+
+impl Default for ChainRegistryBuilder {
+    fn default() -> Self {
+        Self {
+            url: DEFAULT_CHAIN_REGISTRY_URL.to_string(),
+            git_ref: DEFAULT_CHAIN_REGISTRY_REF.to_string(),
+            cache_dir: None,
+            fetch_policy: FetchPolicy::default(),
+            auth: None,
+        }
+    }
+}
+
+impl ChainRegistryBuilder {
+    /// Creates a builder for the upstream registry with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the git url of the registry to clone.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Sets the git ref (branch, tag or commit) to checkout.
+    pub fn git_ref(mut self, git_ref: impl Into<String>) -> Self {
+        self.git_ref = git_ref.into();
+        self
+    }
+
+    /// Sets the directory the registry is cloned into. Defaults to a directory derived from the
+    /// url and ref under the XDG cache directory, see [`ChainRegistryBuilder::default_cache_dir`].
+    pub fn cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    /// Sets when the local clone is updated from the remote.
+    pub fn fetch_policy(mut self, fetch_policy: FetchPolicy) -> Self {
+        self.fetch_policy = fetch_policy;
+        self
+    }
+
+    /// Sets the credentials used to clone and fetch the registry.
+    pub fn auth(mut self, auth: GitAuth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// The default cache directory for this builder's url and ref. This is
+    /// `$XDG_CACHE_HOME/cosmos-chain-registry/<url>@<ref>`, falling back to `$HOME/.cache` and
+    /// then the system temp directory when those are not set.
+    pub fn default_cache_dir(&self) -> PathBuf {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME")
+                    .filter(|dir| !dir.is_empty())
+                    .map(|home| PathBuf::from(home).join(".cache"))
+            })
+            .unwrap_or_else(std::env::temp_dir);
+
+        let slug: String = format!("{}@{}", self.url, self.git_ref)
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' | '@' => c,
+                _ => '-',
+            })
+            .collect();
+
+        base.join("cosmos-chain-registry").join(slug)
+    }
+
+    /// Clones or updates the registry according to the fetch policy and loads it.
+    ///
+    /// Unless the policy is [`FetchPolicy::Never`], the cache directory is locked while it is
+    /// updated and loaded, so builders sharing it, in this process or another, wait for each
+    /// other instead of racing on the clone.
+    pub fn build(self) -> Result<ChainRegistry, Error> {
+        let repo_path = self
+            .cache_dir
+            .clone()
+            .unwrap_or_else(|| self.default_cache_dir());
+        let _lock = match self.fetch_policy {
+            FetchPolicy::Never => None,
+            _ => Some(lock(&repo_path)?),
+        };
+        let exists = repo_path.join(".git").exists();
+
+        match (self.fetch_policy, exists) {
+            (FetchPolicy::Never, _) | (FetchPolicy::IfMissing, true) => {
+                debug!("Using cached chain registry at {}", repo_path.display());
+            }
+            (_, false) => self.clone_repo(&repo_path)?,
+            (FetchPolicy::Always, true) => self.update_repo(&repo_path)?,
+        }
+
+        ChainRegistry::from_path(repo_path)
+    }
+
+    /// Clones the registry into `path` and checks out the configured ref.
+    fn clone_repo(&self, path: &Path) -> Result<(), Error> {
+        info!(
+            "Cloning chain registry from {} to {}",
+            self.url,
+            path.display()
+        );
+
+        std::fs::create_dir_all(path).map_err(|e| Error::io(path, e))?;
+        let repo = RepoBuilder::new()
+            .fetch_options(self.fetch_options())
+            .clone(&self.url, path)?;
+
+        self.checkout(&repo)
+    }
+
+    /// Fetches the configured ref from the remote of an existing clone and checks it out.
+    fn update_repo(&self, path: &Path) -> Result<(), Error> {
+        debug!("Chain registry already exists, pulling latest changes");
+
+        let repo = Repository::open(path)?;
+        if repo.find_remote("origin")?.url() != Some(self.url.as_str()) {
+            info!(
+                "Chain registry at {} was cloned from another remote, switching it to {}",
+                path.display(),
+                self.url
+            );
+            repo.remote_set_url("origin", &self.url)?;
+        }
+
+        let mut remote = repo.find_remote("origin")?;
+        remote.fetch(
+            &[self.git_ref.as_str()],
+            Some(&mut self.fetch_options()),
+            None,
+        )?;
+
+        self.checkout(&repo)
+    }
+
+    /// Checks out the configured ref, preferring the remote tracking branch so that fetched
+    /// changes are picked up, and falling back to tags and commit ids.
+    fn checkout(&self, repo: &Repository) -> Result<(), Error> {
+        let object = repo
+            .revparse_single(&format!("origin/{}", self.git_ref))
+            .or_else(|_| repo.revparse_single(&self.git_ref))?;
+        let commit = object.peel_to_commit()?;
+
+        repo.checkout_tree(
+            commit.as_object(),
+            Some(git2::build::CheckoutBuilder::new().force()),
+        )?;
+        repo.set_head_detached(commit.id())?;
+
+        Ok(())
+    }
+
+    /// Fetch options carrying the configured credentials, if any.
+    fn fetch_options(&self) -> FetchOptions<'_> {
+        let mut callbacks = RemoteCallbacks::new();
+        if let Some(auth) = &self.auth {
+            callbacks.credentials(move |_url, username_from_url, _allowed| match auth {
+                GitAuth::UserPass { username, password } => {
+                    Cred::userpass_plaintext(username, password)
+                }
+                GitAuth::Token(token) => Cred::userpass_plaintext("x-access-token", token),
+                GitAuth::SshAgent { username } => {
+                    Cred::ssh_key_from_agent(username_from_url.unwrap_or(username))
+                }
+            });
+        }
+
+        let mut fo = FetchOptions::new();
+        fo.remote_callbacks(callbacks);
+        fo
+    }
+}
+
+/// Takes an exclusive lock on `<cache_dir>.lock`, released when the returned file is dropped.
+fn lock(cache_dir: &Path) -> Result<std::fs::File, Error> {
+    let mut lock_path = cache_dir.as_os_str().to_owned();
+    lock_path.push(".lock");
+    let lock_path = PathBuf::from(lock_path);
+
+    if let Some(dir) = lock_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+    }
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| Error::io(&lock_path, e))?;
+    file.lock_exclusive()
+        .map_err(|e| Error::io(&lock_path, e))?;
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a local git repository containing the fixture registry to clone from.
+    fn fixture_remote() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        copy_dir(Path::new("tests/fixtures/chain-registry"), dir.path());
+
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let commit = repo
+            .commit(None, &sig, &sig, "registry", &tree, &[])
+            .unwrap();
+        repo.branch("master", &repo.find_commit(commit).unwrap(), true)
+            .unwrap();
+        repo.set_head("refs/heads/master").unwrap();
+
+        dir
+    }
+
+    fn copy_dir(from: &Path, to: &Path) {
+        for entry in std::fs::read_dir(from).unwrap() {
+            let entry = entry.unwrap();
+            let target = to.join(entry.file_name());
+            if entry.file_type().unwrap().is_dir() {
+                std::fs::create_dir_all(&target).unwrap();
+                copy_dir(&entry.path(), &target);
+            } else {
+                std::fs::copy(entry.path(), target).unwrap();
+            }
+        }
+    }
+
+    /// Commits a copy of the fixture juno chain as `chain_name` to a remote made by
+    /// [`fixture_remote`].
+    fn commit_chain(remote: &Path, chain_name: &str, chain_id: &str) {
+        let repo = Repository::open(remote).unwrap();
+        let chain = std::fs::read_to_string(remote.join("juno/chain.json"))
+            .unwrap()
+            .replace(
+                "\"chain_name\": \"juno\"",
+                &format!("\"chain_name\": \"{}\"", chain_name),
+            )
+            .replace(
+                "\"chain_id\": \"juno-1\"",
+                &format!("\"chain_id\": \"{}\"", chain_id),
+            );
+        std::fs::create_dir_all(remote.join(chain_name)).unwrap();
+        std::fs::write(remote.join(chain_name).join("chain.json"), chain).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index
+            .add_path(&Path::new(chain_name).join("chain.json"))
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, chain_name, &tree, &[&parent])
+            .unwrap();
+    }
+
+    #[test]
+    fn can_clone_and_update_from_a_local_remote() {
+        let remote = fixture_remote();
+        let cache = tempfile::tempdir().unwrap();
+        let builder = ChainRegistryBuilder::new()
+            .url(remote.path().to_string_lossy())
+            .cache_dir(cache.path().join("registry"))
+            .fetch_policy(FetchPolicy::Always);
+
+        let registry = builder.clone().build().unwrap();
+        assert_eq!(
+            registry.get_by_chain_id("juno-1").unwrap().chain_name,
+            "juno"
+        );
+        assert!(registry.get_by_chain_name("junofork").is_err());
+
+        // A second build fetches the new upstream commit into the existing clone
+        commit_chain(remote.path(), "junofork", "junofork-1");
+        let registry = builder.build().unwrap();
+        assert_eq!(
+            registry.get_by_chain_id("junofork-1").unwrap().chain_name,
+            "junofork"
+        );
+        assert!(registry.get_by_chain_id("uni-6").is_ok());
+    }
+
+    #[test]
+    fn concurrent_builds_share_a_cache_dir() {
+        let remote = fixture_remote();
+        let cache = tempfile::tempdir().unwrap();
+        let builder = ChainRegistryBuilder::new()
+            .url(remote.path().to_string_lossy())
+            .cache_dir(cache.path().join("registry"));
+
+        std::thread::scope(|scope| {
+            let builds: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| builder.clone().build()))
+                .collect();
+            for build in builds {
+                let registry = build.join().unwrap().unwrap();
+                assert!(registry.get_by_chain_id("juno-1").is_ok());
+            }
+        });
+    }
+
+    #[test]
+    fn updates_switch_the_origin_to_the_configured_url() {
+        let remote = fixture_remote();
+        let fork = fixture_remote();
+        let cache = tempfile::tempdir().unwrap();
+        let path = cache.path().join("registry");
+
+        ChainRegistryBuilder::new()
+            .url(remote.path().to_string_lossy())
+            .cache_dir(&path)
+            .build()
+            .unwrap();
+        ChainRegistryBuilder::new()
+            .url(fork.path().to_string_lossy())
+            .cache_dir(&path)
+            .build()
+            .unwrap();
+
+        let repo = Repository::open(&path).unwrap();
+        let origin = repo.find_remote("origin").unwrap();
+        assert_eq!(origin.url(), Some(&*fork.path().to_string_lossy()));
+    }
+
+    #[test]
+    fn never_fetch_policy_requires_an_existing_registry() {
+        let cache = tempfile::tempdir().unwrap();
+        let registry = ChainRegistryBuilder::new()
+            .cache_dir(cache.path())
+            .fetch_policy(FetchPolicy::Never)
+            .build();
+        assert!(registry.is_err());
+
+        let registry = ChainRegistryBuilder::new()
+            .cache_dir("tests/fixtures/chain-registry")
+            .fetch_policy(FetchPolicy::Never)
+            .build()
+            .unwrap();
+        assert_eq!(
+            registry.get_by_chain_id("juno-1").unwrap().chain_name,
+            "juno"
+        );
+    }
+
+    #[test]
+    fn default_cache_dirs_do_not_collide() {
+        let upstream = ChainRegistryBuilder::new();
+        let fork = ChainRegistryBuilder::new().url("https://github.com/example/chain-registry");
+        let pinned = ChainRegistryBuilder::new().git_ref("v1.0.0");
+
+        assert_ne!(upstream.default_cache_dir(), fork.default_cache_dir());
+        assert_ne!(upstream.default_cache_dir(), pinned.default_cache_dir());
+        assert!(upstream
+            .default_cache_dir()
+            .ends_with("cosmos-chain-registry/https---github.com-cosmos-chain-registry@master"));
+    }
+}
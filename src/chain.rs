@@ -1,6 +1,10 @@
 //! Contains models for serializing and deserializing the `chain.json` in a given chain's directory in the registry repository
 //! Taken from [here](https://github.com/PeggyJV/chain-registry/blob/main/src/chain.rs).
+use crate::fee::GasPrice;
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
 
 /// Information about a Cosmos SDK chain.
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
@@ -12,8 +16,8 @@ pub struct ChainInfo {
     #[serde(rename = "$schema")]
     pub schema: String,
     pub chain_name: String,
-    pub status: String,
-    pub network_type: String,
+    pub status: ChainStatus,
+    pub network_type: NetworkType,
     pub pretty_name: String,
     pub chain_id: String,
     pub bech32_prefix: String,
@@ -28,14 +32,137 @@ pub struct ChainInfo {
     pub staking: Staking,
     pub website: String,
     pub update_link: String,
-    pub key_algos: Vec<String>,
+    pub key_algos: Vec<KeyAlgo>,
     pub explorers: Vec<Explorer>,
 }
 
+/// Implements the conversions of an enum over the known values of a registry string field, with
+/// an `Unknown` variant keeping any other value as-is so it still deserializes and round-trips.
+macro_rules! registry_enum {
+    ($name:ident { $($variant:ident => $value:literal),+ $(,)? }) => {
+        impl $name {
+            /// The value as written in the registry.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $value,)+
+                    Self::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::Unknown(String::new())
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                match value {
+                    $($value => Self::$variant,)+
+                    _ => Self::Unknown(value.to_string()),
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                match Self::from(value.as_str()) {
+                    Self::Unknown(_) => Self::Unknown(value),
+                    known => known,
+                }
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                match value {
+                    $name::Unknown(value) => value,
+                    known => known.as_str().to_string(),
+                }
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                Ok(Self::from(value))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+/// The `status` of a chain, or of an IBC channel.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum ChainStatus {
+    Live,
+    Upcoming,
+    Killed,
+    /// A status this crate does not know of, or none.
+    Unknown(String),
+}
+
+registry_enum!(ChainStatus {
+    Live => "live",
+    Upcoming => "upcoming",
+    Killed => "killed",
+});
+
+/// The `network_type` of a chain.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum NetworkType {
+    Mainnet,
+    Testnet,
+    Devnet,
+    /// A network type this crate does not know of, or none.
+    Unknown(String),
+}
+
+registry_enum!(NetworkType {
+    Mainnet => "mainnet",
+    Testnet => "testnet",
+    Devnet => "devnet",
+});
+
+/// A key algorithm listed in a chain's `key_algos`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum KeyAlgo {
+    Secp256k1,
+    /// Ethermint's Ethereum compatible keys.
+    EthSecp256k1,
+    Ed25519,
+    Sr25519,
+    /// A key algorithm this crate does not know of.
+    Unknown(String),
+}
+
+registry_enum!(KeyAlgo {
+    Secp256k1 => "secp256k1",
+    EthSecp256k1 => "ethsecp256k1",
+    Ed25519 => "ed25519",
+    Sr25519 => "sr25519",
+});
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
 pub struct Genesis {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The genesis file, possibly compressed and carrying a `?checksum=` suffix.
     pub genesis_url: String,
+    /// The interchain security consumer state of a consumer chain's genesis.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ics_ccv_url: Option<String>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
@@ -67,6 +194,39 @@ pub struct Binaries {
     pub windows_amd_64: String,
 }
 
+impl Binaries {
+    /// The url of the binary for a platform such as `linux/amd64`, `None` if it is not listed.
+    pub fn get(&self, platform: &str) -> Option<&str> {
+        let url = match platform {
+            "linux/amd64" => &self.linux_amd_64,
+            "linux/arm64" => &self.linux_arm_64,
+            "darwin/amd64" => &self.darwin_amd_64,
+            "darwin/arm64" => &self.darwin_arm_64,
+            "windows/amd64" => &self.windows_amd_64,
+            _ => return None,
+        };
+        Some(url.as_str()).filter(|url| !url.is_empty())
+    }
+
+    /// The url of the binary for the platform this crate was compiled for.
+    pub fn for_current_platform(&self) -> Option<&str> {
+        self.get(current_platform()?)
+    }
+}
+
+/// The registry's name of the platform this crate was compiled for, e.g. `linux/amd64`, or
+/// `None` if the registry does not list binaries for it.
+pub fn current_platform() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => Some("linux/amd64"),
+        ("linux", "aarch64") => Some("linux/arm64"),
+        ("macos", "x86_64") => Some("darwin/amd64"),
+        ("macos", "aarch64") => Some("darwin/arm64"),
+        ("windows", "x86_64") => Some("windows/amd64"),
+        _ => None,
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
 pub struct Peers {
@@ -84,9 +244,11 @@ pub struct Seed {
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
 pub struct PersistentPeer {
     pub id: String,
     pub address: String,
+    pub provider: Option<String>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
@@ -99,6 +261,59 @@ pub struct Apis {
     pub grpc: Vec<Grpc>,
 }
 
+impl Apis {
+    /// The endpoints of the given kind, in the order the registry lists them.
+    pub fn endpoints(&self, kind: ApiKind) -> Vec<Endpoint> {
+        let endpoint = |address: &String, provider: &Option<String>| Endpoint {
+            kind,
+            address: address.clone(),
+            provider: provider.clone(),
+        };
+
+        match kind {
+            ApiKind::Rpc => self
+                .rpc
+                .iter()
+                .map(|rpc| endpoint(&rpc.address, &rpc.provider))
+                .collect(),
+            ApiKind::Rest => self
+                .rest
+                .iter()
+                .map(|rest| endpoint(&rest.address, &rest.provider))
+                .collect(),
+            ApiKind::Grpc => self
+                .grpc
+                .iter()
+                .map(|grpc| endpoint(&grpc.address, &grpc.provider))
+                .collect(),
+        }
+    }
+
+    /// Every rpc, rest and grpc endpoint, in that order.
+    pub fn all_endpoints(&self) -> Vec<Endpoint> {
+        [ApiKind::Rpc, ApiKind::Rest, ApiKind::Grpc]
+            .into_iter()
+            .flat_map(|kind| self.endpoints(kind))
+            .collect()
+    }
+}
+
+/// The kind of an API listed in [`Apis`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ApiKind {
+    Rpc,
+    Rest,
+    Grpc,
+}
+
+/// An rpc, rest or grpc endpoint of a chain.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Endpoint {
+    pub kind: ApiKind,
+    pub address: String,
+    pub provider: Option<String>,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
 pub struct Rpc {
@@ -131,10 +346,14 @@ pub struct Fees {
 #[serde(default)]
 pub struct FeeToken {
     pub denom: String,
-    pub fixed_min_gas_price: f32,
-    pub low_gas_price: f32,
-    pub average_gas_price: f32,
-    pub high_gas_price: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed_min_gas_price: Option<GasPrice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub low_gas_price: Option<GasPrice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_gas_price: Option<GasPrice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub high_gas_price: Option<GasPrice>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
@@ -155,6 +374,17 @@ pub struct StakingToken {
 pub struct Explorer {
     pub kind: String,
     pub url: String,
+    /// The transaction page template, containing `${txHash}`.
     pub tx_page: String,
+    /// The account page template, containing `${accountAddress}`.
     pub account_page: String,
+    /// The validator page template, containing `${validatorAddress}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validator_page: Option<String>,
+    /// The governance proposal page template, containing `${proposalId}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proposal_page: Option<String>,
+    /// The block page template, containing `${blockHeight}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_page: Option<String>,
 }
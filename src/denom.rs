@@ -0,0 +1,268 @@
+//! Contains ICS-20 denom traces and the computation of the `ibc/<hash>` denoms they are known by
+//! on the receiving chain.
+use crate::ibc::TRANSFER_PORT;
+use crate::index::RegistryIndex;
+use crate::{Asset, Error};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// The prefix of the denoms of tokens received over IBC.
+pub const IBC_DENOM_PREFIX: &str = "ibc/";
+
+/// The path of a token over IBC: the port and channel hops it was received over, most recent
+/// first, and the denom it has on its origin chain.
+///
+/// ## Example
+///
+/// ```rust
+/// use cosmos_chain_registry::denom::DenomTrace;
+///
+/// let trace = DenomTrace::new("uatom").hop("transfer", "channel-0");
+///
+/// assert_eq!(trace.path(), "transfer/channel-0/uatom");
+/// assert_eq!(
+///     trace.ibc_denom(),
+///     "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DenomTrace {
+    /// The `(port_id, channel_id)` hops on the receiving side, most recent first.
+    pub hops: Vec<(String, String)>,
+    pub base_denom: String,
+}
+
+impl DenomTrace {
+    /// A trace of a token on its origin chain, without any hops.
+    pub fn new(base_denom: impl Into<String>) -> Self {
+        Self {
+            hops: Vec::new(),
+            base_denom: base_denom.into(),
+        }
+    }
+
+    /// Adds a hop received over the given port and channel on the receiving chain. Hops are
+    /// added in the order the token travels, so the last hop added is the most recent.
+    pub fn hop(mut self, port_id: impl Into<String>, channel_id: impl Into<String>) -> Self {
+        self.hops.insert(0, (port_id.into(), channel_id.into()));
+        self
+    }
+
+    /// Parses a full denom path such as `transfer/channel-1/transfer/channel-0/uatom`. Base
+    /// denoms containing slashes, e.g. `gamm/pool/1`, are supported.
+    pub fn parse(path: &str) -> Self {
+        let segments: Vec<&str> = path.split('/').collect();
+        let mut hops = Vec::new();
+        let mut i = 0;
+
+        while i + 2 < segments.len() && segments[i + 1].starts_with("channel-") {
+            hops.push((segments[i].to_string(), segments[i + 1].to_string()));
+            i += 2;
+        }
+
+        Self {
+            hops,
+            base_denom: segments[i..].join("/"),
+        }
+    }
+
+    /// The full denom path, e.g. `transfer/channel-0/uatom`.
+    pub fn path(&self) -> String {
+        self.to_string()
+    }
+
+    /// The denom of the token on the receiving chain: `ibc/` followed by the upper case hex
+    /// sha256 of the full path, or the base denom itself if the trace has no hops.
+    pub fn ibc_denom(&self) -> String {
+        if self.hops.is_empty() {
+            self.base_denom.clone()
+        } else {
+            ibc_denom(&self.path())
+        }
+    }
+}
+
+impl fmt::Display for DenomTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (port_id, channel_id) in &self.hops {
+            write!(f, "{}/{}/", port_id, channel_id)?;
+        }
+        write!(f, "{}", self.base_denom)
+    }
+}
+
+/// Computes the ICS-20 denom of a full denom path, e.g. `transfer/channel-0/uatom`.
+pub fn ibc_denom(path: &str) -> String {
+    let hash = Sha256::digest(path.as_bytes());
+    let hex: String = hash.iter().map(|byte| format!("{:02X}", byte)).collect();
+    format!("{}{}", IBC_DENOM_PREFIX, hex)
+}
+
+/// Computes the ICS-20 denom of `base_denom` received over the given `transfer` channel.
+pub fn transfer_denom(channel_id: &str, base_denom: &str) -> String {
+    DenomTrace::new(base_denom)
+        .hop(TRANSFER_PORT, channel_id)
+        .ibc_denom()
+}
+
+/// An `ibc/<hash>` denom resolved to the chain and asset it originates from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolvedDenom {
+    /// The chain_name of the origin chain.
+    pub chain_name: String,
+    /// The asset on the origin chain.
+    pub asset: Asset,
+    /// The path of the denom on the chain it was resolved on.
+    pub trace: DenomTrace,
+}
+
+/// The maximum number of hops followed when resolving a denom, guarding against trace cycles.
+const MAX_RESOLVE_DEPTH: usize = 8;
+
+/// Resolves an `ibc/<hash>` denom on `chain_name` to its origin chain and asset, see
+/// [`ChainRegistry::resolve_ibc_denom`](crate::ChainRegistry::resolve_ibc_denom).
+pub(crate) fn resolve(
+    index: &RegistryIndex,
+    chain_name: &str,
+    denom: &str,
+) -> Result<ResolvedDenom, Error> {
+    resolve_at_depth(index, chain_name, denom, 0)
+        .ok_or_else(|| Error::NotFound(format!("denom {} on {}", denom, chain_name)))
+}
+
+fn resolve_at_depth(
+    index: &RegistryIndex,
+    chain_name: &str,
+    denom: &str,
+    depth: usize,
+) -> Option<ResolvedDenom> {
+    let hash = denom.strip_prefix(IBC_DENOM_PREFIX)?;
+    let ibc_denom = format!("{}{}", IBC_DENOM_PREFIX, hash.to_uppercase());
+
+    // Follow the most recent ibc trace of the asset if the chain lists it
+    let listed = index.assets(chain_name).ok().and_then(|assets| {
+        let asset = assets
+            .assets
+            .iter()
+            .find(|asset| asset.base.eq_ignore_ascii_case(&ibc_denom))?;
+        let trace = asset
+            .traces
+            .iter()
+            .rev()
+            .find(|trace| trace.kind == "ibc")?;
+        let channel_id = trace.chain.as_ref()?.channel_id.clone()?;
+        Some((
+            trace.counterparty.chain_name.clone(),
+            channel_id,
+            trace.counterparty.base_denom.clone(),
+        ))
+    });
+
+    // Otherwise hash the full trace of the assets of every chain connected over a transfer
+    // channel, so vouchers the counterparty received over IBC itself are matched too
+    let hop = listed.or_else(|| {
+        index.ibc_data().iter().find_map(|ibc_data| {
+            ibc_data
+                .channels_from(chain_name)?
+                .into_iter()
+                .filter(|channel| channel.is_transfer())
+                .find_map(|channel| {
+                    let assets = index.assets(&channel.counterparty_chain_name).ok()?;
+                    assets
+                        .assets
+                        .iter()
+                        .find(|asset| {
+                            asset_trace(index, asset, depth).is_some_and(|trace| {
+                                trace.hop(TRANSFER_PORT, &channel.channel_id).ibc_denom()
+                                    == ibc_denom
+                            })
+                        })
+                        .map(|asset| {
+                            (
+                                channel.counterparty_chain_name.clone(),
+                                channel.channel_id.clone(),
+                                asset.base.clone(),
+                            )
+                        })
+                })
+        })
+    });
+
+    let (counterparty, channel_id, base_denom) = hop?;
+    let resolved = if base_denom.starts_with(IBC_DENOM_PREFIX) && depth < MAX_RESOLVE_DEPTH {
+        // The counterparty received the token over IBC itself, resolve it there
+        resolve_at_depth(index, &counterparty, &base_denom, depth + 1)?
+    } else {
+        ResolvedDenom {
+            asset: index
+                .assets(&counterparty)
+                .ok()?
+                .get_by_base(&base_denom)?
+                .clone(),
+            chain_name: counterparty,
+            trace: DenomTrace::new(base_denom),
+        }
+    };
+
+    Some(ResolvedDenom {
+        trace: resolved.trace.hop(TRANSFER_PORT, channel_id),
+        ..resolved
+    })
+}
+
+/// The full trace of a listed asset, following its most recent `ibc` trace back to the origin
+/// chain when the asset is an `ibc/` voucher. Returns `None` if the trace cannot be followed.
+fn asset_trace(index: &RegistryIndex, asset: &Asset, depth: usize) -> Option<DenomTrace> {
+    if !asset.base.starts_with(IBC_DENOM_PREFIX) {
+        return Some(DenomTrace::new(asset.base.as_str()));
+    }
+    if depth >= MAX_RESOLVE_DEPTH {
+        return None;
+    }
+
+    let trace = asset
+        .traces
+        .iter()
+        .rev()
+        .find(|trace| trace.kind == "ibc")?;
+    let chain = trace.chain.as_ref()?;
+    if let Some(path) = &chain.path {
+        return Some(DenomTrace::parse(path));
+    }
+
+    let counterparty = index
+        .assets(&trace.counterparty.chain_name)
+        .ok()?
+        .get_by_base(&trace.counterparty.base_denom)?;
+    let port_id = chain.port.as_deref().unwrap_or(TRANSFER_PORT);
+    Some(asset_trace(index, counterparty, depth + 1)?.hop(port_id, chain.channel_id.clone()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_hashes_denom_traces() {
+        let trace = DenomTrace::parse("transfer/channel-1/transfer/channel-0/gamm/pool/1");
+        assert_eq!(
+            trace.hops,
+            [
+                ("transfer".to_string(), "channel-1".to_string()),
+                ("transfer".to_string(), "channel-0".to_string())
+            ]
+        );
+        assert_eq!(trace.base_denom, "gamm/pool/1");
+        assert_eq!(
+            trace.path(),
+            "transfer/channel-1/transfer/channel-0/gamm/pool/1"
+        );
+
+        assert_eq!(DenomTrace::parse("uatom"), DenomTrace::new("uatom"));
+        assert_eq!(DenomTrace::new("uatom").ibc_denom(), "uatom");
+        assert_eq!(
+            transfer_denom("channel-42", "ujuno"),
+            "ibc/46B44899322F3CD854D2D46DEEF881958467CDD4B3B10086DA49296BBED94BED"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Contains the load diagnostics of a registry directory, reporting for every chain directory
+//! and `_IBC` directory which of the known registry files parsed into this crate's models and
+//! which fields of them are not modeled. Useful to detect schema drift after the registry is
+//! refreshed.
+use crate::ibc::IbcData;
+use crate::{index, AssetList, ChainInfo, Error};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// Parses a registry file into its model, see [`diagnose_file`].
+type FileCheck = fn(&Path, &str) -> Result<FileDiagnostics, Error>;
+
+/// The registry files that are checked in every chain directory, with the model they parse into.
+const KNOWN_FILES: &[(&str, FileCheck)] = &[
+    ("chain.json", diagnose_file::<ChainInfo>),
+    ("assetlist.json", diagnose_file::<AssetList>),
+];
+
+/// Diagnostics for every chain directory of a registry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegistryDiagnostics {
+    pub chains: Vec<ChainDiagnostics>,
+    /// The `_IBC` directories, with a file for every IBC connection.
+    pub ibc: Vec<ChainDiagnostics>,
+}
+
+impl RegistryDiagnostics {
+    /// Returns `true` when every known file that exists parsed without unknown fields.
+    pub fn is_clean(&self) -> bool {
+        self.chains
+            .iter()
+            .chain(&self.ibc)
+            .flat_map(|chain| &chain.files)
+            .all(|file| !file.status.is_failed() && file.unknown_fields.is_empty())
+    }
+
+    /// Returns the files that failed to parse, along with the directory they are in.
+    pub fn failures(&self) -> impl Iterator<Item = (&str, &FileDiagnostics)> {
+        self.chains.iter().chain(&self.ibc).flat_map(|chain| {
+            chain
+                .files
+                .iter()
+                .filter(|file| file.status.is_failed())
+                .map(move |file| (chain.directory.as_str(), file))
+        })
+    }
+}
+
+/// Diagnostics for the known files of a single chain directory, or the files of an `_IBC`
+/// directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChainDiagnostics {
+    /// The directory relative to the registry root, e.g. `testnets/junotestnet` or `_IBC`.
+    pub directory: String,
+    pub files: Vec<FileDiagnostics>,
+}
+
+/// Diagnostics for a single registry file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileDiagnostics {
+    /// The file name, e.g. `chain.json`.
+    pub file: String,
+    pub status: FileStatus,
+    /// Paths of the fields present in the file but not in the model, e.g. `codebase.consensus`.
+    pub unknown_fields: Vec<String>,
+}
+
+/// The outcome of parsing a registry file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FileStatus {
+    /// The file parsed into its model.
+    Parsed,
+    /// The file does not exist in the chain directory.
+    Missing,
+    /// The file failed to parse, with the serde error and its location.
+    Failed {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+}
+
+impl FileStatus {
+    /// Returns `true` if the file failed to parse.
+    pub fn is_failed(&self) -> bool {
+        matches!(self, FileStatus::Failed { .. })
+    }
+}
+
+/// Parses every known file of every chain directory under `root`, and every file of its `_IBC`
+/// directories.
+pub(crate) fn diagnose(root: &Path) -> Result<RegistryDiagnostics, Error> {
+    let chains = index::chain_dirs(root)?
+        .into_iter()
+        .filter(|dir| KNOWN_FILES.iter().any(|(file, _)| dir.join(file).exists()))
+        .map(|dir| {
+            let files = KNOWN_FILES
+                .iter()
+                .map(|(file, check)| check(&dir, file))
+                .collect::<Result<_, _>>()?;
+
+            Ok(ChainDiagnostics {
+                directory: index::relative_dir(root, &dir),
+                files,
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+
+    let ibc = index::ibc_dirs(root)
+        .into_iter()
+        .map(|dir| {
+            let mut paths = index::glob_files(&dir, "*.json")?;
+            paths.sort();
+            let files = paths
+                .iter()
+                .map(|path| {
+                    let file = path.file_name().unwrap_or_default().to_string_lossy();
+                    diagnose_file::<IbcData>(&dir, &file)
+                })
+                .collect::<Result<_, _>>()?;
+
+            Ok(ChainDiagnostics {
+                directory: index::relative_dir(root, &dir),
+                files,
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+
+    Ok(RegistryDiagnostics { chains, ibc })
+}
+
+/// Parses `dir/file` into `T`, collecting the fields that `T` does not model.
+fn diagnose_file<T: DeserializeOwned>(dir: &Path, file: &str) -> Result<FileDiagnostics, Error> {
+    let path = dir.join(file);
+    let mut unknown_fields = Vec::new();
+
+    let status = if !path.exists() {
+        FileStatus::Missing
+    } else {
+        let contents = std::fs::read(&path).map_err(|e| Error::io(&path, e))?;
+        let mut de = serde_json::Deserializer::from_slice(&contents);
+        let res: Result<T, _> =
+            serde_ignored::deserialize(&mut de, |field| unknown_fields.push(field.to_string()));
+
+        match res.and_then(|_| de.end()) {
+            Ok(()) => FileStatus::Parsed,
+            Err(e) => FileStatus::Failed {
+                message: e.to_string(),
+                line: e.line(),
+                column: e.column(),
+            },
+        }
+    };
+
+    Ok(FileDiagnostics {
+        file: file.to_string(),
+        status,
+        unknown_fields,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_failures_and_unknown_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("good")).unwrap();
+        std::fs::create_dir_all(dir.path().join("testnets/broken")).unwrap();
+        std::fs::write(
+            dir.path().join("good/chain.json"),
+            r#"{ "chain_id": "good-1", "codebase": { "consensus": { "type": "cometbft" } }, "fees": { "fee_tokens": [{ "denom": "ugood", "low_gas_price": 0.10 }] } }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("testnets/broken/chain.json"),
+            "{\n  \"chain_id\": \"broken-1\",\n  \"slip44\": \"118\"\n}",
+        )
+        .unwrap();
+
+        std::fs::create_dir_all(dir.path().join("_IBC")).unwrap();
+        std::fs::write(
+            dir.path().join("_IBC/good-other.json"),
+            r#"{ "chain_1": { "chain_name": "good" }, "chain_2": { "chain_name": "other" }, "operators": [] }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("_IBC/broken-other.json"),
+            "{ \"channels\": 1 }",
+        )
+        .unwrap();
+
+        let diagnostics = diagnose(dir.path()).unwrap();
+        assert!(!diagnostics.is_clean());
+        assert_eq!(diagnostics.chains.len(), 2);
+
+        let good = &diagnostics.chains[0];
+        assert_eq!(good.directory, "good");
+        assert_eq!(good.files[0].status, FileStatus::Parsed);
+        assert_eq!(good.files[0].unknown_fields, ["codebase.consensus"]);
+        assert_eq!(good.files[1].file, "assetlist.json");
+        assert_eq!(good.files[1].status, FileStatus::Missing);
+
+        let ibc = &diagnostics.ibc[0];
+        assert_eq!(ibc.directory, "_IBC");
+        assert_eq!(ibc.files[1].file, "good-other.json");
+        assert_eq!(ibc.files[1].status, FileStatus::Parsed);
+        assert_eq!(ibc.files[1].unknown_fields, ["operators"]);
+
+        let failures: Vec<_> = diagnostics.failures().collect();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].0, "testnets/broken");
+        match &failures[0].1.status {
+            FileStatus::Failed { line, .. } => assert_eq!(*line, 3),
+            other => panic!("expected a failure, got {:?}", other),
+        }
+        assert_eq!(failures[1].0, "_IBC");
+        assert_eq!(failures[1].1.file, "broken-other.json");
+    }
+}
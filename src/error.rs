@@ -0,0 +1,104 @@
+//! Contains the [`Error`] type returned by this crate.
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Errors returned when fetching, loading or querying the registry.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Cloning, fetching or checking out the registry repository failed.
+    #[error("git error: {0}")]
+    Git(#[from] git2::Error),
+
+    /// Reading or writing a file or directory failed.
+    #[error("failed to access {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// A registry file is not valid JSON or does not match its model. The serde error carries the
+    /// line and column of the failure.
+    #[error("failed to parse {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: Arc<serde_json::Error>,
+    },
+
+    /// The directory does not look like a chain registry.
+    #[error("{} is not a chain registry: {reason}", path.display())]
+    InvalidRegistry { path: PathBuf, reason: String },
+
+    /// Nothing matches the requested key: a chain, asset, IBC connection, endpoint, etc. The
+    /// message names what was looked up, e.g. `chain juno-1` or `rpc endpoint of juno`.
+    #[error("not found: {0}")]
+    NotFound(String),
+
+    /// An address is not a valid bech32 address.
+    #[error("invalid address {address}: {reason}")]
+    InvalidAddress { address: String, reason: String },
+
+    /// More than one `chain.json` in the registry declares the same chain_id.
+    #[error("chain_id {chain_id} is declared by more than one chain: {paths:?}")]
+    DuplicateChainId {
+        chain_id: String,
+        paths: Vec<PathBuf>,
+    },
+
+    /// More than one `chain.json` in the registry declares the same chain_name.
+    #[error("chain_name {chain_name} is declared by more than one chain: {paths:?}")]
+    DuplicateChainName {
+        chain_name: String,
+        paths: Vec<PathBuf>,
+    },
+
+    /// More than one `assetlist.json` in the registry belongs to the same chain_name.
+    #[error("chain_name {chain_name} has more than one asset list: {paths:?}")]
+    DuplicateAssetList {
+        chain_name: String,
+        paths: Vec<PathBuf>,
+    },
+
+    /// Downloading a file failed, or its checksum uses an unsupported algorithm.
+    #[error("failed to fetch {url}: {reason}")]
+    Fetch { url: String, reason: String },
+
+    /// A downloaded file does not match the checksum published for it.
+    #[error("checksum mismatch for {url}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+
+    /// The registry published no checksum for a binary, so it cannot be verified before it is
+    /// written.
+    #[error("no checksum published for {url}")]
+    MissingChecksum { url: String },
+
+    /// A genesis file cannot be decompressed or parsed, or is for another chain.
+    #[error("invalid genesis {url}: {reason}")]
+    InvalidGenesis { url: String, reason: String },
+
+    /// A fee cannot be computed from the chain's gas prices.
+    #[error("invalid fee for {chain}: {reason}")]
+    InvalidFee { chain: String, reason: String },
+}
+
+impl Error {
+    /// Creates an [`Error::Io`] for the given path.
+    pub(crate) fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Self::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+impl From<glob::GlobError> for Error {
+    fn from(e: glob::GlobError) -> Self {
+        let path = e.path().to_path_buf();
+        Self::io(path, e.into())
+    }
+}
@@ -0,0 +1,203 @@
+//! Contains the rendering of block explorer links from the page templates in a chain's
+//! `explorers`.
+use crate::chain::Explorer;
+use crate::ChainInfo;
+
+/// A kind of page an explorer links to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExplorerPage {
+    Tx,
+    Account,
+    Validator,
+    Proposal,
+    Block,
+}
+
+impl ExplorerPage {
+    /// The placeholder substituted in the page's template, e.g. `${txHash}`.
+    pub fn placeholder(self) -> &'static str {
+        match self {
+            ExplorerPage::Tx => "${txHash}",
+            ExplorerPage::Account => "${accountAddress}",
+            ExplorerPage::Validator => "${validatorAddress}",
+            ExplorerPage::Proposal => "${proposalId}",
+            ExplorerPage::Block => "${blockHeight}",
+        }
+    }
+}
+
+impl Explorer {
+    /// The template of a page, `None` if the explorer does not list it.
+    pub fn template(&self, page: ExplorerPage) -> Option<&str> {
+        let template = match page {
+            ExplorerPage::Tx => Some(&self.tx_page),
+            ExplorerPage::Account => Some(&self.account_page),
+            ExplorerPage::Validator => self.validator_page.as_ref(),
+            ExplorerPage::Proposal => self.proposal_page.as_ref(),
+            ExplorerPage::Block => self.block_page.as_ref(),
+        };
+        template
+            .map(String::as_str)
+            .filter(|template| !template.is_empty())
+    }
+
+    /// Renders a page's link, `None` if the explorer does not list its template.
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - The kind of page.
+    /// * `value` - The value substituted for the page's placeholder, e.g. a tx hash.
+    pub fn link(&self, page: ExplorerPage, value: &str) -> Option<String> {
+        self.template(page)
+            .map(|template| template.replace(page.placeholder(), value))
+    }
+
+    /// The link to a transaction.
+    pub fn tx_link(&self, tx_hash: &str) -> Option<String> {
+        self.link(ExplorerPage::Tx, tx_hash)
+    }
+
+    /// The link to an account.
+    pub fn account_link(&self, address: &str) -> Option<String> {
+        self.link(ExplorerPage::Account, address)
+    }
+
+    /// The link to a validator, by its `valoper` address.
+    pub fn validator_link(&self, validator_address: &str) -> Option<String> {
+        self.link(ExplorerPage::Validator, validator_address)
+    }
+
+    /// The link to a governance proposal.
+    pub fn proposal_link(&self, proposal_id: u64) -> Option<String> {
+        self.link(ExplorerPage::Proposal, &proposal_id.to_string())
+    }
+
+    /// The link to a block.
+    pub fn block_link(&self, height: u64) -> Option<String> {
+        self.link(ExplorerPage::Block, &height.to_string())
+    }
+}
+
+impl ChainInfo {
+    /// The explorer of the earliest listed kind, ignoring case, e.g. `["mintscan", "ping.pub"]`,
+    /// falling back to the first explorer of the chain.
+    pub fn preferred_explorer(&self, kinds: &[&str]) -> Option<&Explorer> {
+        self.explorers_by_preference(kinds).into_iter().next()
+    }
+
+    /// Renders a page's link with the first explorer listing its template, trying the
+    /// explorers of the given kinds first, see [`ChainInfo::preferred_explorer`].
+    pub fn explorer_link(&self, page: ExplorerPage, value: &str, kinds: &[&str]) -> Option<String> {
+        self.explorers_by_preference(kinds)
+            .into_iter()
+            .find_map(|explorer| explorer.link(page, value))
+    }
+
+    /// The explorers that do not list the template of a page.
+    pub fn explorers_without(&self, page: ExplorerPage) -> Vec<&Explorer> {
+        self.explorers
+            .iter()
+            .filter(|explorer| explorer.template(page).is_none())
+            .collect()
+    }
+
+    /// The explorers of the given kinds in order, then every explorer of the chain.
+    fn explorers_by_preference(&self, kinds: &[&str]) -> Vec<&Explorer> {
+        kinds
+            .iter()
+            .flat_map(|kind| {
+                self.explorers
+                    .iter()
+                    .filter(|explorer| explorer.kind.eq_ignore_ascii_case(kind))
+            })
+            .chain(&self.explorers)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain_info() -> ChainInfo {
+        ChainInfo {
+            explorers: vec![
+                Explorer {
+                    kind: "ping.pub".to_string(),
+                    url: "https://ping.pub/juno".to_string(),
+                    tx_page: "https://ping.pub/juno/tx/${txHash}".to_string(),
+                    ..Default::default()
+                },
+                Explorer {
+                    kind: "mintscan".to_string(),
+                    url: "https://www.mintscan.io/juno".to_string(),
+                    tx_page: "https://www.mintscan.io/juno/txs/${txHash}".to_string(),
+                    account_page: "https://www.mintscan.io/juno/account/${accountAddress}"
+                        .to_string(),
+                    validator_page: Some(
+                        "https://www.mintscan.io/juno/validators/${validatorAddress}".to_string(),
+                    ),
+                    proposal_page: Some(
+                        "https://www.mintscan.io/juno/proposals/${proposalId}".to_string(),
+                    ),
+                    block_page: Some(
+                        "https://www.mintscan.io/juno/blocks/${blockHeight}".to_string(),
+                    ),
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn renders_explorer_links() {
+        let info = chain_info();
+        let mintscan = info.preferred_explorer(&["Mintscan"]).unwrap();
+        assert_eq!(mintscan.kind, "mintscan");
+        assert_eq!(
+            info.preferred_explorer(&["unknown"]).unwrap().kind,
+            "ping.pub"
+        );
+
+        assert_eq!(
+            mintscan.tx_link("ABC").as_deref(),
+            Some("https://www.mintscan.io/juno/txs/ABC")
+        );
+        assert_eq!(
+            mintscan.account_link("juno1xyz").as_deref(),
+            Some("https://www.mintscan.io/juno/account/juno1xyz")
+        );
+        assert_eq!(
+            mintscan.validator_link("junovaloper1xyz").as_deref(),
+            Some("https://www.mintscan.io/juno/validators/junovaloper1xyz")
+        );
+        assert_eq!(
+            mintscan.proposal_link(42).as_deref(),
+            Some("https://www.mintscan.io/juno/proposals/42")
+        );
+        assert_eq!(
+            mintscan.block_link(100).as_deref(),
+            Some("https://www.mintscan.io/juno/blocks/100")
+        );
+
+        // ping.pub is tried first but lists no account page
+        assert_eq!(
+            info.explorer_link(ExplorerPage::Tx, "ABC", &["ping.pub"])
+                .as_deref(),
+            Some("https://ping.pub/juno/tx/ABC")
+        );
+        assert_eq!(
+            info.explorer_link(ExplorerPage::Account, "juno1xyz", &["ping.pub"])
+                .as_deref(),
+            Some("https://www.mintscan.io/juno/account/juno1xyz")
+        );
+
+        let missing: Vec<&str> = info
+            .explorers_without(ExplorerPage::Block)
+            .iter()
+            .map(|explorer| explorer.kind.as_str())
+            .collect();
+        assert_eq!(missing, ["ping.pub"]);
+        assert!(info.explorers_without(ExplorerPage::Tx).is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+//! Contains the generation of [Hermes](https://hermes.informal.systems) relayer configs.
+use super::{channels_between, grpc_addr, is_ethermint, rpc_addr};
+use crate::fee::GasPriceTier;
+use crate::{ChainInfo, ChainRegistry, Error};
+use serde::{Deserialize, Serialize};
+
+/// Generates a Hermes `config.toml` relaying between the given chains. Each chain's packet
+/// filter allows the registry's channels to the other chains, leaving out killed channels.
+///
+/// Returns [`Error::NotFound`] if a chain is not in the registry or lists no rpc or grpc
+/// endpoint.
+///
+/// ## Example
+///
+/// ```no_run
+/// use cosmos_chain_registry::export::hermes;
+/// use cosmos_chain_registry::ChainRegistry;
+///
+/// let registry = ChainRegistry::from_path("./chain-registry").unwrap();
+/// let config = hermes::config(&registry, &["juno", "osmosis"]).unwrap();
+///
+/// std::fs::write("config.toml", config.to_toml()).unwrap();
+/// ```
+pub fn config(registry: &ChainRegistry, chain_names: &[&str]) -> Result<HermesConfig, Error> {
+    let chains = chain_names
+        .iter()
+        .map(|chain_name| {
+            let chain_info = registry.get_by_chain_name(chain_name)?;
+            let mut chain = HermesChain::from_chain_info(&chain_info)?;
+            chain.packet_filter.list = channels_between(registry, chain_name, chain_names)
+                .into_iter()
+                .map(|channel| (channel.port_id, channel.channel_id))
+                .collect();
+            Ok(chain)
+        })
+        .collect::<Result<_, Error>>()?;
+
+    Ok(HermesConfig {
+        chains,
+        ..Default::default()
+    })
+}
+
+/// A Hermes `config.toml`. The global sections hold Hermes' recommended defaults.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct HermesConfig {
+    pub global: Global,
+    pub mode: Mode,
+    pub rest: Service,
+    pub telemetry: Service,
+    pub chains: Vec<HermesChain>,
+}
+
+impl Default for HermesConfig {
+    fn default() -> Self {
+        Self {
+            global: Global::default(),
+            mode: Mode::default(),
+            rest: Service::default(),
+            telemetry: Service {
+                port: 3001,
+                ..Service::default()
+            },
+            chains: Vec::new(),
+        }
+    }
+}
+
+impl HermesConfig {
+    /// Serializes the config to TOML.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("hermes config serializes to toml")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Global {
+    pub log_level: String,
+}
+
+impl Default for Global {
+    fn default() -> Self {
+        Self {
+            log_level: "info".to_string(),
+        }
+    }
+}
+
+/// Which kinds of IBC objects Hermes relays.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Mode {
+    pub clients: ModeClients,
+    pub connections: ModeToggle,
+    pub channels: ModeToggle,
+    pub packets: ModePackets,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Self {
+            clients: ModeClients {
+                enabled: true,
+                refresh: true,
+                misbehaviour: false,
+            },
+            connections: ModeToggle { enabled: false },
+            channels: ModeToggle { enabled: false },
+            packets: ModePackets {
+                enabled: true,
+                clear_interval: 100,
+                clear_on_start: true,
+                tx_confirmation: false,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ModeClients {
+    pub enabled: bool,
+    pub refresh: bool,
+    pub misbehaviour: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ModeToggle {
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ModePackets {
+    pub enabled: bool,
+    pub clear_interval: u64,
+    pub clear_on_start: bool,
+    pub tx_confirmation: bool,
+}
+
+/// The `rest` and `telemetry` servers, disabled by default.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Service {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+}
+
+impl Default for Service {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "127.0.0.1".to_string(),
+            port: 3000,
+        }
+    }
+}
+
+/// A `[[chains]]` entry.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct HermesChain {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub rpc_addr: String,
+    pub grpc_addr: String,
+    pub rpc_timeout: String,
+    pub account_prefix: String,
+    pub key_name: String,
+    pub store_prefix: String,
+    pub default_gas: u64,
+    pub max_gas: u64,
+    pub gas_multiplier: f64,
+    pub max_msg_num: u32,
+    pub max_tx_size: u64,
+    pub clock_drift: String,
+    pub max_block_time: String,
+    pub event_source: EventSource,
+    pub gas_price: GasPrice,
+    pub trust_threshold: TrustThreshold,
+    pub address_type: AddressType,
+    pub packet_filter: PacketFilter,
+}
+
+impl HermesChain {
+    /// The entry of a chain with Hermes' recommended defaults and an empty packet filter.
+    pub fn from_chain_info(chain_info: &ChainInfo) -> Result<Self, Error> {
+        let rpc_addr = rpc_addr(chain_info)?;
+        let websocket = rpc_addr
+            .replacen("https://", "wss://", 1)
+            .replacen("http://", "ws://", 1);
+        let fee_token = chain_info.default_fee_token();
+
+        Ok(Self {
+            id: chain_info.chain_id.clone(),
+            kind: "CosmosSdk".to_string(),
+            grpc_addr: grpc_addr(chain_info)?,
+            rpc_addr,
+            rpc_timeout: "10s".to_string(),
+            account_prefix: chain_info.bech32_prefix.clone(),
+            key_name: chain_info.chain_name.clone(),
+            store_prefix: "ibc".to_string(),
+            default_gas: 100_000,
+            max_gas: 400_000,
+            gas_multiplier: 1.1,
+            max_msg_num: 30,
+            max_tx_size: 2_097_152,
+            clock_drift: "5s".to_string(),
+            max_block_time: "30s".to_string(),
+            event_source: EventSource {
+                mode: "push".to_string(),
+                url: format!("{}/websocket", websocket),
+                batch_delay: "500ms".to_string(),
+            },
+            gas_price: GasPrice {
+                price: fee_token
+                    .map(|fee_token| fee_token.gas_price(GasPriceTier::Average).to_f64())
+                    .unwrap_or_default(),
+                denom: fee_token
+                    .map(|fee_token| fee_token.denom.clone())
+                    .unwrap_or_default(),
+            },
+            trust_threshold: TrustThreshold {
+                numerator: "1".to_string(),
+                denominator: "3".to_string(),
+            },
+            address_type: AddressType::for_chain(chain_info),
+            packet_filter: PacketFilter {
+                policy: "allow".to_string(),
+                list: Vec::new(),
+            },
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct EventSource {
+    pub mode: String,
+    pub url: String,
+    pub batch_delay: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct GasPrice {
+    pub price: f64,
+    pub denom: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct TrustThreshold {
+    pub numerator: String,
+    pub denominator: String,
+}
+
+/// How account addresses are derived from keys.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct AddressType {
+    /// `cosmos` or `ethermint`.
+    pub derivation: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proto_type: Option<ProtoType>,
+}
+
+impl AddressType {
+    /// `ethermint` for chains signing with `ethsecp256k1` keys, `cosmos` otherwise.
+    pub fn for_chain(chain_info: &ChainInfo) -> Self {
+        if is_ethermint(chain_info) {
+            Self {
+                derivation: "ethermint".to_string(),
+                proto_type: Some(ProtoType {
+                    pk_type: "/ethermint.crypto.v1.ethsecp256k1.PubKey".to_string(),
+                }),
+            }
+        } else {
+            Self {
+                derivation: "cosmos".to_string(),
+                proto_type: None,
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ProtoType {
+    pub pk_type: String,
+}
+
+/// The channels Hermes relays packets on.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PacketFilter {
+    /// `allow` or `deny`.
+    pub policy: String,
+    /// The `(port_id, channel_id)` pairs the policy applies to.
+    pub list: Vec<(String, String)>,
+}
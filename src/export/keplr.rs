@@ -0,0 +1,179 @@
+//! Contains the conversion of a chain to the chain info taken by Keplr's
+//! [`experimentalSuggestChain`](https://docs.keplr.app/api/suggest-chain).
+use super::{is_ethermint, rest_addr, rpc_addr};
+use crate::fee::GasPriceTier;
+use crate::{Asset, AssetList, ChainInfo, ChainRegistry, Error};
+use serde::{Deserialize, Serialize};
+
+/// Generates the Keplr chain info of a chain from its `chain.json` and `assetlist.json`.
+///
+/// Returns [`Error::NotFound`] if the chain is not in the registry, has no asset list, lists
+/// no rpc or rest endpoint, or a fee or staking token is missing from its asset list.
+///
+/// ## Example
+///
+/// ```no_run
+/// use cosmos_chain_registry::export::keplr;
+/// use cosmos_chain_registry::ChainRegistry;
+///
+/// let registry = ChainRegistry::from_path("./chain-registry").unwrap();
+/// let chain_info = keplr::suggest_chain(&registry, "juno").unwrap();
+///
+/// println!("{}", serde_json::to_string(&chain_info).unwrap());
+/// ```
+pub fn suggest_chain(registry: &ChainRegistry, chain_name: &str) -> Result<KeplrChainInfo, Error> {
+    let chain_info = registry.get_by_chain_name(chain_name)?;
+    let assets = registry.get_assets(chain_name)?;
+    KeplrChainInfo::new(&chain_info, &assets)
+}
+
+/// The chain info taken by Keplr's `experimentalSuggestChain`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeplrChainInfo {
+    pub chain_id: String,
+    pub chain_name: String,
+    pub rpc: String,
+    pub rest: String,
+    pub bip44: Bip44,
+    pub bech32_config: Bech32Config,
+    pub currencies: Vec<Currency>,
+    pub fee_currencies: Vec<FeeCurrency>,
+    pub stake_currency: Currency,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub features: Vec<String>,
+}
+
+impl KeplrChainInfo {
+    /// Converts a chain and its assets. The currencies are every asset of the asset list, the
+    /// fee currencies are the `fee_tokens` with their gas prices and the stake currency is the
+    /// first of the `staking_tokens`.
+    pub fn new(chain_info: &ChainInfo, assets: &AssetList) -> Result<Self, Error> {
+        let currency = |denom: &str| {
+            assets
+                .get_by_base(denom)
+                .map(Currency::from_asset)
+                .ok_or_else(|| {
+                    Error::NotFound(format!("asset {} of {}", denom, chain_info.chain_name))
+                })
+        };
+
+        let fee_currencies = chain_info
+            .fees
+            .fee_tokens
+            .iter()
+            .map(|fee_token| {
+                Ok(FeeCurrency {
+                    currency: currency(&fee_token.denom)?,
+                    gas_price_step: GasPriceStep {
+                        low: fee_token.gas_price(GasPriceTier::Low).to_f64(),
+                        average: fee_token.gas_price(GasPriceTier::Average).to_f64(),
+                        high: fee_token.gas_price(GasPriceTier::High).to_f64(),
+                    },
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        let stake_denom = chain_info
+            .staking
+            .staking_tokens
+            .first()
+            .map(|staking_token| staking_token.denom.as_str())
+            .ok_or_else(|| {
+                Error::NotFound(format!("staking token of {}", chain_info.chain_name))
+            })?;
+
+        let mut features = Vec::new();
+        if chain_info.codebase.cosmwasm_enabled {
+            features.push("cosmwasm".to_string());
+        }
+        if is_ethermint(chain_info) {
+            features.push("eth-address-gen".to_string());
+            features.push("eth-key-sign".to_string());
+        }
+
+        Ok(Self {
+            chain_id: chain_info.chain_id.clone(),
+            chain_name: chain_info.pretty_name.clone(),
+            rpc: rpc_addr(chain_info)?,
+            rest: rest_addr(chain_info)?,
+            bip44: Bip44 {
+                coin_type: chain_info.slip44,
+            },
+            bech32_config: Bech32Config::new(&chain_info.bech32_prefix),
+            currencies: assets.assets.iter().map(Currency::from_asset).collect(),
+            fee_currencies,
+            stake_currency: currency(stake_denom)?,
+            features,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bip44 {
+    pub coin_type: u32,
+}
+
+/// The bech32 prefixes of the chain's accounts, validators and consensus nodes.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bech32Config {
+    pub bech32_prefix_acc_addr: String,
+    pub bech32_prefix_acc_pub: String,
+    pub bech32_prefix_val_addr: String,
+    pub bech32_prefix_val_pub: String,
+    pub bech32_prefix_cons_addr: String,
+    pub bech32_prefix_cons_pub: String,
+}
+
+impl Bech32Config {
+    /// The cosmos-sdk's prefixes derived from the account prefix, e.g. `junovaloper`.
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            bech32_prefix_acc_addr: prefix.to_string(),
+            bech32_prefix_acc_pub: format!("{}pub", prefix),
+            bech32_prefix_val_addr: format!("{}valoper", prefix),
+            bech32_prefix_val_pub: format!("{}valoperpub", prefix),
+            bech32_prefix_cons_addr: format!("{}valcons", prefix),
+            bech32_prefix_cons_pub: format!("{}valconspub", prefix),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Currency {
+    /// The display symbol, e.g. `JUNO`.
+    pub coin_denom: String,
+    /// The base denom, e.g. `ujuno`.
+    pub coin_minimal_denom: String,
+    pub coin_decimals: u32,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub coin_gecko_id: Option<String>,
+}
+
+impl Currency {
+    pub fn from_asset(asset: &Asset) -> Self {
+        Self {
+            coin_denom: asset.symbol.clone(),
+            coin_minimal_denom: asset.base.clone(),
+            coin_decimals: asset.decimals(),
+            coin_gecko_id: asset.coingecko_id.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeCurrency {
+    #[serde(flatten)]
+    pub currency: Currency,
+    pub gas_price_step: GasPriceStep,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct GasPriceStep {
+    pub low: f64,
+    pub average: f64,
+    pub high: f64,
+}
@@ -0,0 +1,69 @@
+//! Contains exporters turning registry data into the configuration files of relayers, wallets
+//! and nodes.
+use crate::chain::KeyAlgo;
+use crate::{ChainInfo, ChainRegistry, ChannelInfo, Error};
+
+pub mod hermes;
+pub mod keplr;
+pub mod node;
+pub mod rly;
+
+/// The first rest address of the chain, without a trailing slash.
+pub(crate) fn rest_addr(chain_info: &ChainInfo) -> Result<String, Error> {
+    chain_info
+        .apis
+        .rest
+        .first()
+        .map(|rest| rest.address.trim_end_matches('/').to_string())
+        .ok_or_else(|| Error::NotFound(format!("rest endpoint of {}", chain_info.chain_name)))
+}
+
+/// Returns `true` if the chain signs with ethermint's `ethsecp256k1` keys.
+pub(crate) fn is_ethermint(chain_info: &ChainInfo) -> bool {
+    chain_info.key_algos.contains(&KeyAlgo::EthSecp256k1)
+}
+
+/// The first rpc address of the chain, without a trailing slash.
+pub(crate) fn rpc_addr(chain_info: &ChainInfo) -> Result<String, Error> {
+    chain_info
+        .apis
+        .rpc
+        .first()
+        .map(|rpc| rpc.address.trim_end_matches('/').to_string())
+        .ok_or_else(|| Error::NotFound(format!("rpc endpoint of {}", chain_info.chain_name)))
+}
+
+/// The first grpc address of the chain as a url. The registry often omits the scheme, port
+/// 443 is then assumed to use tls.
+pub(crate) fn grpc_addr(chain_info: &ChainInfo) -> Result<String, Error> {
+    let address = chain_info
+        .apis
+        .grpc
+        .first()
+        .map(|grpc| grpc.address.trim_end_matches('/'))
+        .ok_or_else(|| Error::NotFound(format!("grpc endpoint of {}", chain_info.chain_name)))?;
+
+    Ok(if address.contains("://") {
+        address.to_string()
+    } else if address.ends_with(":443") {
+        format!("https://{}", address)
+    } else {
+        format!("http://{}", address)
+    })
+}
+
+/// The channels between every pair of the given chains, as seen from the first chain of the
+/// pair, leaving out channels tagged as `killed`. Pairs without IBC data are skipped.
+pub(crate) fn channels_between(
+    registry: &ChainRegistry,
+    chain_name: &str,
+    chain_names: &[&str],
+) -> Vec<ChannelInfo> {
+    chain_names
+        .iter()
+        .filter(|counterparty| **counterparty != chain_name)
+        .filter_map(|counterparty| registry.ibc_channels(chain_name, counterparty).ok())
+        .flatten()
+        .filter(|channel| !channel.is_killed())
+        .collect()
+}
@@ -0,0 +1,242 @@
+//! Contains the generation of the configuration a new full node needs: the `config.toml` peers,
+//! the `app.toml` minimum gas prices and a `client.toml`.
+use crate::fee::GasPriceTier;
+use crate::{ChainInfo, ChainRegistry, Error};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The rpc address of a node running on the local machine.
+pub const LOCAL_NODE_RPC: &str = "tcp://localhost:26657";
+
+/// Generates the node configuration of a chain.
+///
+/// Returns [`Error::NotFound`] if the chain is not in the registry.
+///
+/// ## Example
+///
+/// ```no_run
+/// use cosmos_chain_registry::export::node;
+/// use cosmos_chain_registry::ChainRegistry;
+///
+/// let registry = ChainRegistry::from_path("./chain-registry").unwrap();
+/// let config = node::config(&registry, "juno").unwrap();
+///
+/// // After `junod init`
+/// config.write(config.home_dir()).unwrap();
+/// ```
+pub fn config(registry: &ChainRegistry, chain_name: &str) -> Result<NodeConfig, Error> {
+    Ok(NodeConfig::new(&registry.get_by_chain_name(chain_name)?))
+}
+
+/// The settings of a full node taken from the registry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NodeConfig {
+    pub chain_id: String,
+    pub daemon_name: String,
+    /// The node home as listed in the registry, e.g. `$HOME/.juno`.
+    pub node_home: String,
+    /// The `config.toml` `p2p.seeds`, e.g. `id@host:26656,...`.
+    pub seeds: String,
+    /// The `config.toml` `p2p.persistent_peers`.
+    pub persistent_peers: String,
+    /// The `app.toml` `minimum-gas-prices`, e.g. `0.0025ujuno`.
+    pub minimum_gas_prices: String,
+    /// The rpc address of the `client.toml`, [`LOCAL_NODE_RPC`] by default.
+    pub node: String,
+}
+
+impl NodeConfig {
+    /// The node configuration of a chain. The minimum gas prices are the `fixed_min_gas_price`
+    /// of every fee token, or its low gas price when the fixed minimum is missing or zero, so
+    /// the node does not accept transactions without fees unless the registry lists no price.
+    pub fn new(chain_info: &ChainInfo) -> Self {
+        let peers = |peers: Vec<(&String, &String)>| {
+            peers
+                .into_iter()
+                .map(|(id, address)| format!("{}@{}", id, address))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        Self {
+            chain_id: chain_info.chain_id.clone(),
+            daemon_name: chain_info.daemon_name.clone(),
+            node_home: chain_info.node_home.clone(),
+            seeds: peers(
+                chain_info
+                    .peers
+                    .seeds
+                    .iter()
+                    .map(|seed| (&seed.id, &seed.address))
+                    .collect(),
+            ),
+            persistent_peers: peers(
+                chain_info
+                    .peers
+                    .persistent_peers
+                    .iter()
+                    .map(|peer| (&peer.id, &peer.address))
+                    .collect(),
+            ),
+            minimum_gas_prices: chain_info
+                .fees
+                .fee_tokens
+                .iter()
+                .map(|fee_token| {
+                    let price = fee_token
+                        .fixed_min_gas_price
+                        .clone()
+                        .filter(|price| !price.is_zero())
+                        .unwrap_or_else(|| fee_token.gas_price(GasPriceTier::Low));
+                    format!("{}{}", price, fee_token.denom)
+                })
+                .collect::<Vec<_>>()
+                .join(","),
+            node: LOCAL_NODE_RPC.to_string(),
+        }
+    }
+
+    /// The node home with `$HOME` or a leading `~` expanded, falling back to the listed value
+    /// if `$HOME` is not set.
+    pub fn home_dir(&self) -> PathBuf {
+        let home = match std::env::var("HOME") {
+            Ok(home) => home,
+            Err(_) => return PathBuf::from(&self.node_home),
+        };
+
+        let expanded = self.node_home.replace("$HOME", &home);
+        match expanded.strip_prefix("~/") {
+            Some(rest) => Path::new(&home).join(rest),
+            None => PathBuf::from(expanded),
+        }
+    }
+
+    /// The `[p2p]` fragment of `config.toml`.
+    pub fn config_toml(&self) -> String {
+        format!(
+            "[p2p]\nseeds = {}\npersistent_peers = {}\n",
+            quote(&self.seeds),
+            quote(&self.persistent_peers)
+        )
+    }
+
+    /// The fragment of `app.toml`.
+    pub fn app_toml(&self) -> String {
+        format!("minimum-gas-prices = {}\n", quote(&self.minimum_gas_prices))
+    }
+
+    /// The complete `client.toml`.
+    pub fn client_toml(&self) -> String {
+        let client = ClientToml {
+            chain_id: self.chain_id.clone(),
+            keyring_backend: "os".to_string(),
+            output: "text".to_string(),
+            node: self.node.clone(),
+            broadcast_mode: "sync".to_string(),
+        };
+        toml::to_string(&client).expect("client.toml serializes to toml")
+    }
+
+    /// Writes the configuration under `<home>/config`. The keys are set in the existing
+    /// `config.toml` and `app.toml`, e.g. the ones written by `<daemon> init`, keeping the
+    /// rest of those files. Missing files are created with only the fragment, `client.toml` is
+    /// always replaced.
+    pub fn write(&self, home: impl AsRef<Path>) -> Result<(), Error> {
+        let dir = home.as_ref().join("config");
+        fs::create_dir_all(&dir).map_err(|e| Error::io(&dir, e))?;
+
+        update_toml(
+            &dir.join("config.toml"),
+            Some("p2p"),
+            &[
+                ("seeds", &self.seeds),
+                ("persistent_peers", &self.persistent_peers),
+            ],
+        )?;
+        update_toml(
+            &dir.join("app.toml"),
+            None,
+            &[("minimum-gas-prices", &self.minimum_gas_prices)],
+        )?;
+
+        let path = dir.join("client.toml");
+        fs::write(&path, self.client_toml()).map_err(|e| Error::io(path, e))
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct ClientToml {
+    chain_id: String,
+    keyring_backend: String,
+    output: String,
+    node: String,
+    broadcast_mode: String,
+}
+
+fn quote(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+/// Sets `keys` in `table` of a toml file, `None` being the root table, creating the file,
+/// table or keys when missing. Works on lines so the comments of the file are kept.
+fn update_toml(path: &Path, table: Option<&str>, keys: &[(&str, &String)]) -> Result<(), Error> {
+    let contents = if path.exists() {
+        fs::read_to_string(path).map_err(|e| Error::io(path, e))?
+    } else {
+        String::new()
+    };
+
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    let is_header = |line: &str| line.trim_start().starts_with('[');
+
+    // The range of lines of the table, after its header
+    let (start, end) = match table {
+        Some(table) => {
+            let header = format!("[{}]", table);
+            let start = match lines.iter().position(|line| line.trim() == header) {
+                Some(i) => i + 1,
+                None => {
+                    if !lines.is_empty() {
+                        lines.push(String::new());
+                    }
+                    lines.push(header);
+                    lines.len()
+                }
+            };
+            let end = lines[start..]
+                .iter()
+                .position(|line| is_header(line))
+                .map_or(lines.len(), |i| start + i);
+            (start, end)
+        }
+        None => (
+            0,
+            lines
+                .iter()
+                .position(|line| is_header(line))
+                .unwrap_or(lines.len()),
+        ),
+    };
+
+    let mut inserted = 0;
+    for (key, value) in keys {
+        let line = format!("{} = {}", key, quote(value));
+        let existing = lines[start..end + inserted].iter().position(|line| {
+            line.split_once('=')
+                .is_some_and(|(name, _)| name.trim() == *key)
+        });
+        match existing {
+            Some(i) => lines[start + i] = line,
+            None => {
+                lines.insert(start + inserted, line);
+                inserted += 1;
+            }
+        }
+    }
+
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    fs::write(path, contents).map_err(|e| Error::io(path, e))
+}
@@ -0,0 +1,207 @@
+//! Contains the generation of [Go relayer](https://github.com/cosmos/relayer) chain and path
+//! configs.
+use super::{is_ethermint, rpc_addr};
+use crate::fee::GasPriceTier;
+use crate::{ChainInfo, ChainRegistry, Error};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Generates the rly chain configs of the given chains and the paths between every pair of
+/// them the registry has IBC data for. Each path's channel filter allows the registry's
+/// channels, leaving out killed channels.
+///
+/// Returns [`Error::NotFound`] if a chain is not in the registry or lists no rpc endpoint.
+///
+/// ## Example
+///
+/// ```no_run
+/// use cosmos_chain_registry::export::rly;
+/// use cosmos_chain_registry::ChainRegistry;
+///
+/// let registry = ChainRegistry::from_path("./chain-registry").unwrap();
+/// let config = rly::config(&registry, &["juno", "osmosis"]).unwrap();
+///
+/// // Then `rly chains add --file rly/chains/juno.json juno`, ...
+/// config.write("rly").unwrap();
+/// ```
+pub fn config(registry: &ChainRegistry, chain_names: &[&str]) -> Result<RlyConfig, Error> {
+    let mut config = RlyConfig::default();
+
+    for chain_name in chain_names {
+        let chain_info = registry.get_by_chain_name(chain_name)?;
+        config.chains.insert(
+            chain_name.to_string(),
+            RlyChain::from_chain_info(&chain_info)?,
+        );
+    }
+
+    for (i, chain_a) in chain_names.iter().enumerate() {
+        for chain_b in &chain_names[i + 1..] {
+            let ibc_data = match registry.get_ibc_data(chain_a, chain_b) {
+                Ok(ibc_data) => ibc_data,
+                Err(_) => continue,
+            };
+            let src = registry.get_by_chain_name(&ibc_data.chain_1.chain_name)?;
+            let dst = registry.get_by_chain_name(&ibc_data.chain_2.chain_name)?;
+            let channels = ibc_data
+                .channels_from(&src.chain_name)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|channel| !channel.is_killed())
+                .map(|channel| channel.channel_id)
+                .collect();
+
+            let path = RlyPath {
+                src: PathEnd {
+                    chain_id: src.chain_id,
+                    client_id: ibc_data.chain_1.client_id.clone(),
+                    connection_id: ibc_data.chain_1.connection_id.clone(),
+                },
+                dst: PathEnd {
+                    chain_id: dst.chain_id,
+                    client_id: ibc_data.chain_2.client_id.clone(),
+                    connection_id: ibc_data.chain_2.connection_id.clone(),
+                },
+                src_channel_filter: ChannelFilter {
+                    rule: "allowlist".to_string(),
+                    channel_list: channels,
+                },
+            };
+            let name = format!(
+                "{}-{}",
+                ibc_data.chain_1.chain_name, ibc_data.chain_2.chain_name
+            );
+            config.paths.insert(name, path);
+        }
+    }
+
+    Ok(config)
+}
+
+/// The rly chain and path configs, keyed by chain_name and by path name, e.g. `juno-osmosis`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RlyConfig {
+    pub chains: BTreeMap<String, RlyChain>,
+    pub paths: BTreeMap<String, RlyPath>,
+}
+
+impl RlyConfig {
+    /// Writes every chain to `<dir>/chains/<chain_name>.json` and every path to
+    /// `<dir>/paths/<path_name>.json`, the files `rly chains add --file` and
+    /// `rly paths add --file` take.
+    pub fn write(&self, dir: impl AsRef<Path>) -> Result<(), Error> {
+        let dir = dir.as_ref();
+        write_json_files(&dir.join("chains"), &self.chains)?;
+        write_json_files(&dir.join("paths"), &self.paths)
+    }
+}
+
+fn write_json_files<T: Serialize>(dir: &Path, files: &BTreeMap<String, T>) -> Result<(), Error> {
+    fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+    for (name, value) in files {
+        let path = dir.join(format!("{}.json", name));
+        let json = serde_json::to_string_pretty(value).expect("rly config serializes to JSON");
+        fs::write(&path, json).map_err(|e| Error::io(path, e))?;
+    }
+    Ok(())
+}
+
+/// A chain config, as taken by `rly chains add --file`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RlyChain {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: RlyChainValue,
+}
+
+impl RlyChain {
+    /// The config of a chain with the relayer's recommended defaults.
+    pub fn from_chain_info(chain_info: &ChainInfo) -> Result<Self, Error> {
+        let gas_prices = chain_info
+            .default_fee_token()
+            .map(|fee_token| {
+                format!(
+                    "{}{}",
+                    fee_token.gas_price(GasPriceTier::Average),
+                    fee_token.denom
+                )
+            })
+            .unwrap_or_default();
+        let extra_codecs = if is_ethermint(chain_info) {
+            vec!["ethermint".to_string()]
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            kind: "cosmos".to_string(),
+            value: RlyChainValue {
+                key: "default".to_string(),
+                chain_id: chain_info.chain_id.clone(),
+                rpc_addr: rpc_addr(chain_info)?,
+                account_prefix: chain_info.bech32_prefix.clone(),
+                keyring_backend: "test".to_string(),
+                gas_adjustment: 1.2,
+                gas_prices,
+                min_gas_amount: 0,
+                debug: false,
+                timeout: "20s".to_string(),
+                output_format: "json".to_string(),
+                sign_mode: "direct".to_string(),
+                extra_codecs,
+                coin_type: chain_info.slip44,
+                broadcast_mode: "batch".to_string(),
+            },
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RlyChainValue {
+    pub key: String,
+    pub chain_id: String,
+    pub rpc_addr: String,
+    pub account_prefix: String,
+    pub keyring_backend: String,
+    pub gas_adjustment: f64,
+    /// The gas price with its denom, e.g. `0.0025uosmo`.
+    pub gas_prices: String,
+    pub min_gas_amount: u64,
+    pub debug: bool,
+    pub timeout: String,
+    pub output_format: String,
+    pub sign_mode: String,
+    pub extra_codecs: Vec<String>,
+    /// The chain's `slip44`.
+    pub coin_type: u32,
+    pub broadcast_mode: String,
+}
+
+/// A path config, as taken by `rly paths add --file`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RlyPath {
+    pub src: PathEnd,
+    pub dst: PathEnd,
+    pub src_channel_filter: ChannelFilter,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PathEnd {
+    pub chain_id: String,
+    pub client_id: String,
+    pub connection_id: String,
+}
+
+/// The channels of the path's source chain the relayer relays on.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ChannelFilter {
+    /// `allowlist` or `denylist`.
+    pub rule: String,
+    pub channel_list: Vec<String>,
+}
@@ -0,0 +1,500 @@
+//! Contains the exact decimal [`GasPrice`] of the registry's `fee_tokens` and the estimation of
+//! transaction fees from them.
+use crate::chain::FeeToken;
+use crate::{ChainInfo, Error};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::de::{self, MapAccess, Unexpected, Visitor};
+use serde::{ser, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue;
+use std::fmt;
+use std::str::FromStr;
+
+/// A gas price tier, as listed in `fee_tokens`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GasPriceTier {
+    Low,
+    #[default]
+    Average,
+    High,
+}
+
+/// A gas price in the base unit of its denom.
+///
+/// It keeps the JSON number text of the registry, so `0.10` is written back as `0.10` and `1e-7`
+/// as `1e-7`, along with its exact decimal value. Prices beyond the range of a [`Decimal`], like
+/// `1e30`, are still read but have no decimal value.
+///
+/// The text is kept when reading and writing JSON with serde_json, and written as a string by
+/// binary formats. Other formats read and write the price as a number.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GasPrice {
+    text: String,
+    decimal: Option<Decimal>,
+}
+
+impl GasPrice {
+    /// A gas price of `price`, written as its decimal text, e.g. `0.025`.
+    pub fn new(price: Decimal) -> Self {
+        Self {
+            text: price.to_string(),
+            decimal: Some(price),
+        }
+    }
+
+    /// Parses the text of a JSON number, or of a number formatted by Rust.
+    fn from_json(text: &str) -> Result<Self, String> {
+        let is_number = text.starts_with(|c: char| c == '-' || c.is_ascii_digit())
+            && text.ends_with(|c: char| c.is_ascii_digit())
+            && text.parse::<f64>().is_ok();
+        if !is_number {
+            return Err(format!("expected a decimal number, found {}", text));
+        }
+
+        Ok(Self {
+            text: text.to_string(),
+            decimal: Decimal::from_str(text)
+                .or_else(|_| Decimal::from_scientific(text))
+                .ok(),
+        })
+    }
+
+    /// The price as written in the registry, e.g. `0.0025` or `1e-7`.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The exact price, `None` if it is beyond the range of a [`Decimal`].
+    pub fn decimal(&self) -> Option<Decimal> {
+        self.decimal
+    }
+
+    /// The closest `f64`, for display or configs taking floats.
+    pub fn to_f64(&self) -> f64 {
+        self.text.parse().unwrap_or_default()
+    }
+
+    /// Returns `true` if the price is zero, e.g. a `fixed_min_gas_price` of `0`.
+    pub fn is_zero(&self) -> bool {
+        self.to_f64() == 0.0
+    }
+
+    fn is_below(&self, other: &GasPrice) -> bool {
+        match (self.decimal, other.decimal) {
+            (Some(price), Some(other)) => price < other,
+            _ => self.to_f64() < other.to_f64(),
+        }
+    }
+
+    fn midpoint(&self, other: &GasPrice) -> Option<GasPrice> {
+        let sum = self.decimal?.checked_add(other.decimal?)?;
+        Some(Self::new((sum / Decimal::TWO).normalize()))
+    }
+}
+
+impl Default for GasPrice {
+    fn default() -> Self {
+        Self::new(Decimal::ZERO)
+    }
+}
+
+impl From<Decimal> for GasPrice {
+    fn from(price: Decimal) -> Self {
+        Self::new(price)
+    }
+}
+
+impl FromStr for GasPrice {
+    type Err = serde_json::Error;
+
+    fn from_str(price: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(price)
+    }
+}
+
+impl fmt::Display for GasPrice {
+    /// Formats the price as a plain decimal, e.g. `0.0000001` for `1e-7`, or as written in the
+    /// registry if it is beyond the range of a [`Decimal`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.decimal {
+            Some(price) => fmt::Display::fmt(&price, f),
+            None => f.write_str(&self.text),
+        }
+    }
+}
+
+/// The newtype name serde_json deserializes into the raw text of a value, see
+/// [`RawValue`]. Other formats treat it as any newtype and deserialize what it wraps.
+const RAW_VALUE_TOKEN: &str = "$serde_json::private::RawValue";
+
+impl Serialize for GasPrice {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // serde_json writes raw values verbatim, other formats would write them as a struct
+        if std::any::type_name::<S>()
+            .trim_start_matches("&mut ")
+            .starts_with("serde_json::")
+        {
+            return RawValue::from_string(self.text.clone())
+                .map_err(ser::Error::custom)?
+                .serialize(serializer);
+        }
+
+        if !serializer.is_human_readable() {
+            return serializer.serialize_str(&self.text);
+        }
+
+        match self.decimal {
+            Some(price) if price.scale() == 0 => match (price.to_u64(), price.to_i64()) {
+                (Some(price), _) => serializer.serialize_u64(price),
+                (None, Some(price)) => serializer.serialize_i64(price),
+                (None, None) => serializer.serialize_f64(self.to_f64()),
+            },
+            _ => serializer.serialize_f64(self.to_f64()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for GasPrice {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct GasPriceVisitor;
+
+        impl<'de> Visitor<'de> for GasPriceVisitor {
+            type Value = GasPrice;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a decimal number")
+            }
+
+            fn visit_newtype_struct<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<GasPrice, D::Error> {
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_any(self)
+                } else {
+                    deserializer.deserialize_str(self)
+                }
+            }
+
+            // serde_json hands out a raw value as a map from the token to the JSON text
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<GasPrice, A::Error> {
+                match map.next_key::<String>()? {
+                    Some(key) if key == RAW_VALUE_TOKEN => {
+                        let text: String = map.next_value()?;
+                        GasPrice::from_json(&text).map_err(de::Error::custom)
+                    }
+                    _ => Err(de::Error::invalid_type(Unexpected::Map, &self)),
+                }
+            }
+
+            // Binary formats write the text as a string
+            fn visit_str<E: de::Error>(self, price: &str) -> Result<GasPrice, E> {
+                GasPrice::from_json(price).map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, price: u64) -> Result<GasPrice, E> {
+                GasPrice::from_json(&price.to_string()).map_err(E::custom)
+            }
+
+            fn visit_i64<E: de::Error>(self, price: i64) -> Result<GasPrice, E> {
+                GasPrice::from_json(&price.to_string()).map_err(E::custom)
+            }
+
+            fn visit_f64<E: de::Error>(self, price: f64) -> Result<GasPrice, E> {
+                if !price.is_finite() {
+                    return Err(E::invalid_value(Unexpected::Float(price), &self));
+                }
+                GasPrice::from_json(&price.to_string()).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(RAW_VALUE_TOKEN, GasPriceVisitor)
+    }
+}
+
+/// An amount of a denom, in its base unit.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Hash, Serialize)]
+pub struct Coin {
+    pub denom: String,
+    pub amount: u128,
+}
+
+impl fmt::Display for Coin {
+    /// Formats the coin the way the Cosmos SDK does, e.g. `5000ujuno`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.amount, self.denom)
+    }
+}
+
+impl FeeToken {
+    /// The gas price of a tier, never below `fixed_min_gas_price`.
+    ///
+    /// A missing tier falls back to its neighbours: low to average then high, high to average
+    /// then low, and average to the midpoint of low and high, or whichever of them is set. When
+    /// no tier is set the fixed minimum is used, zero if it is missing too.
+    pub fn gas_price(&self, tier: GasPriceTier) -> GasPrice {
+        let low = self.low_gas_price.as_ref();
+        let average = self.average_gas_price.as_ref();
+        let high = self.high_gas_price.as_ref();
+
+        let price = match tier {
+            GasPriceTier::Low => low.or(average).or(high).cloned(),
+            GasPriceTier::Average => average.cloned().or_else(|| match (low, high) {
+                (Some(low), Some(high)) => low.midpoint(high).or_else(|| Some(low.clone())),
+                (low, high) => low.or(high).cloned(),
+            }),
+            GasPriceTier::High => high.or(average).or(low).cloned(),
+        };
+
+        let fixed_min = self.fixed_min_gas_price.clone().unwrap_or_default();
+        match price {
+            Some(price) if !price.is_below(&fixed_min) => price,
+            _ => fixed_min,
+        }
+    }
+}
+
+impl ChainInfo {
+    /// The fee token used to pay for transactions by default: the first one matching a staking
+    /// token, otherwise the first one listed.
+    pub fn default_fee_token(&self) -> Option<&FeeToken> {
+        let fee_tokens = &self.fees.fee_tokens;
+        fee_tokens
+            .iter()
+            .find(|fee_token| {
+                self.staking
+                    .staking_tokens
+                    .iter()
+                    .any(|staking_token| staking_token.denom == fee_token.denom)
+            })
+            .or_else(|| fee_tokens.first())
+    }
+
+    /// Estimates the fee of a transaction, rounding up to a whole amount of the base denom.
+    ///
+    /// Returns [`Error::NotFound`] if the chain lists no fee token, or not the requested one,
+    /// and [`Error::InvalidFee`] if the fee overflows.
+    ///
+    /// # Arguments
+    ///
+    /// * `gas` - The gas used, or the gas limit, of the transaction.
+    /// * `tier` - The gas price tier, see [`FeeToken::gas_price`] for missing tiers.
+    /// * `denom` - The denom to pay in, [`ChainInfo::default_fee_token`] if `None`.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use cosmos_chain_registry::fee::GasPriceTier;
+    /// use cosmos_chain_registry::ChainRegistry;
+    ///
+    /// let registry = ChainRegistry::from_path("./chain-registry").unwrap();
+    /// let info = registry.get_by_chain_name("juno").unwrap();
+    ///
+    /// let fee = info.estimate_fee(200_000, GasPriceTier::Average, None).unwrap();
+    /// println!("{}", fee); // 12500ujuno
+    /// ```
+    pub fn estimate_fee(
+        &self,
+        gas: u64,
+        tier: GasPriceTier,
+        denom: Option<&str>,
+    ) -> Result<Coin, Error> {
+        let fee_token = match denom {
+            Some(denom) => self
+                .fees
+                .fee_tokens
+                .iter()
+                .find(|fee_token| fee_token.denom == denom),
+            None => self.default_fee_token(),
+        }
+        .ok_or_else(|| {
+            Error::NotFound(format!(
+                "fee token {} of {}",
+                denom.unwrap_or_default(),
+                self.chain_name
+            ))
+        })?;
+
+        let overflow = || Error::InvalidFee {
+            chain: self.chain_name.clone(),
+            reason: format!("fee of {} gas overflows", gas),
+        };
+        let amount = fee_token
+            .gas_price(tier)
+            .decimal()
+            .and_then(|price| Decimal::from(gas).checked_mul(price))
+            .ok_or_else(overflow)?
+            .ceil()
+            .to_u128()
+            .ok_or_else(overflow)?;
+
+        Ok(Coin {
+            denom: fee_token.denom.clone(),
+            amount,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::{Fees, Staking, StakingToken};
+
+    fn fee_token(json: &str) -> FeeToken {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn chain_info(fee_tokens: Vec<FeeToken>) -> ChainInfo {
+        ChainInfo {
+            chain_name: "juno".to_string(),
+            fees: Fees { fee_tokens },
+            staking: Staking {
+                staking_tokens: vec![StakingToken {
+                    denom: "ujuno".to_string(),
+                }],
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn round_trips_gas_prices() {
+        let json = r#"{"denom":"ujuno","fixed_min_gas_price":0,"low_gas_price":0.0025,"average_gas_price":1.1,"high_gas_price":7}"#;
+        let token = fee_token(json);
+        assert_eq!(token.low_gas_price, Some("0.0025".parse().unwrap()));
+        assert_eq!(token.low_gas_price.as_ref().unwrap().to_f64(), 0.0025);
+        assert_eq!(token.average_gas_price.as_ref().unwrap().to_string(), "1.1");
+        assert_eq!(serde_json::to_string(&token).unwrap(), json);
+
+        // The text is kept, trailing zeros and exponents included
+        let json = r#"{"denom":"ujuno","fixed_min_gas_price":0.10,"low_gas_price":1e-7,"average_gas_price":1.0}"#;
+        let token = fee_token(json);
+        assert_eq!(token.fixed_min_gas_price.as_ref().unwrap().as_str(), "0.10");
+        assert_eq!(
+            token.low_gas_price.as_ref().unwrap().to_string(),
+            "0.0000001"
+        );
+        assert_eq!(token.average_gas_price.as_ref().unwrap().to_string(), "1.0");
+        assert_eq!(token.high_gas_price, None);
+        assert_eq!(serde_json::to_string(&token).unwrap(), json);
+
+        // Prices beyond the range of a decimal are still read
+        let json = r#"{"denom":"ujuno","high_gas_price":1e30}"#;
+        let token = fee_token(json);
+        let high = token.high_gas_price.as_ref().unwrap();
+        assert_eq!(high.decimal(), None);
+        assert_eq!(high.to_f64(), 1e30);
+        assert_eq!(high.to_string(), "1e30");
+        assert_eq!(serde_json::to_string(&token).unwrap(), json);
+
+        assert_eq!(GasPrice::default().to_string(), "0");
+        assert!("0.0.1".parse::<GasPrice>().is_err());
+        assert!(serde_json::from_str::<FeeToken>(r#"{"low_gas_price":"cheap"}"#).is_err());
+        assert!(serde_json::from_str::<FeeToken>(r#"{"low_gas_price":"0.1"}"#).is_err());
+    }
+
+    #[test]
+    fn round_trips_gas_prices_through_other_formats() {
+        let token = fee_token(
+            r#"{"denom":"ujuno","fixed_min_gas_price":0,"low_gas_price":0.0025,"average_gas_price":1.1,"high_gas_price":1e30}"#,
+        );
+
+        let written = toml::to_string(&token).unwrap();
+        assert!(written.contains("fixed_min_gas_price = 0\n"), "{}", written);
+        assert!(written.contains("low_gas_price = 0.0025\n"), "{}", written);
+        let read: FeeToken = toml::from_str(&written).unwrap();
+        assert_eq!(read.fixed_min_gas_price, token.fixed_min_gas_price);
+        assert_eq!(read.low_gas_price, token.low_gas_price);
+        assert_eq!(read.average_gas_price, token.average_gas_price);
+        assert_eq!(read.high_gas_price.unwrap().to_f64(), 1e30);
+
+        let value = serde_json::to_value(&token).unwrap();
+        let read: FeeToken = serde_json::from_value(value).unwrap();
+        assert_eq!(read.low_gas_price, token.low_gas_price);
+
+        assert!(toml::from_str::<FeeToken>("low_gas_price = \"cheap\"").is_err());
+        assert!(toml::from_str::<FeeToken>("low_gas_price = \"-inf\"").is_err());
+    }
+
+    #[test]
+    fn falls_back_across_gas_price_tiers() {
+        let price = |json: &str, tier| fee_token(json).gas_price(tier).to_string();
+
+        let full = r#"{"fixed_min_gas_price":0.0025,"low_gas_price":0.03,"average_gas_price":0.0625,"high_gas_price":0.1}"#;
+        assert_eq!(price(full, GasPriceTier::Low), "0.03");
+        assert_eq!(price(full, GasPriceTier::Average), "0.0625");
+        assert_eq!(price(full, GasPriceTier::High), "0.1");
+
+        let low_high = r#"{"low_gas_price":0.01,"high_gas_price":0.04}"#;
+        assert_eq!(price(low_high, GasPriceTier::Average), "0.025");
+
+        let average_only = r#"{"average_gas_price":0.025}"#;
+        assert_eq!(price(average_only, GasPriceTier::Low), "0.025");
+        assert_eq!(price(average_only, GasPriceTier::High), "0.025");
+
+        // The fixed minimum is a floor, and the price when no tier is set
+        let fixed_min = r#"{"fixed_min_gas_price":0.05,"low_gas_price":0.01}"#;
+        assert_eq!(price(fixed_min, GasPriceTier::Low), "0.05");
+        assert_eq!(
+            price(r#"{"fixed_min_gas_price":0.0025}"#, GasPriceTier::High),
+            "0.0025"
+        );
+        assert_eq!(price("{}", GasPriceTier::Average), "0");
+
+        let beyond_decimal =
+            r#"{"fixed_min_gas_price":0.01,"low_gas_price":0.1,"high_gas_price":1e30}"#;
+        assert_eq!(price(beyond_decimal, GasPriceTier::Average), "0.1");
+        assert_eq!(price(beyond_decimal, GasPriceTier::High), "1e30");
+    }
+
+    #[test]
+    fn estimates_fees() {
+        let info = chain_info(vec![
+            fee_token(r#"{"denom":"ibc/ATOM","low_gas_price":0.001,"average_gas_price":0.0025}"#),
+            fee_token(
+                r#"{"denom":"ujuno","fixed_min_gas_price":0.0025,"low_gas_price":0.03,"average_gas_price":0.0625}"#,
+            ),
+        ]);
+
+        let fee = info
+            .estimate_fee(200_000, GasPriceTier::Average, None)
+            .unwrap();
+        assert_eq!(
+            fee,
+            Coin {
+                denom: "ujuno".to_string(),
+                amount: 12_500,
+            }
+        );
+        assert_eq!(fee.to_string(), "12500ujuno");
+
+        // Rounded up to a whole base unit
+        let fee = info
+            .estimate_fee(100_001, GasPriceTier::Low, Some("ibc/ATOM"))
+            .unwrap();
+        assert_eq!(fee.to_string(), "101ibc/ATOM");
+
+        assert!(matches!(
+            info.estimate_fee(100_000, GasPriceTier::Low, Some("uosmo")),
+            Err(Error::NotFound(_))
+        ));
+        assert!(matches!(
+            chain_info(Vec::new()).estimate_fee(100_000, GasPriceTier::Low, None),
+            Err(Error::NotFound(_))
+        ));
+        assert!(matches!(
+            chain_info(vec![fee_token(
+                r#"{"denom":"ujuno","average_gas_price":1e20}"#
+            )])
+            .estimate_fee(u64::MAX, GasPriceTier::Average, None),
+            Err(Error::InvalidFee { .. })
+        ));
+        assert!(matches!(
+            chain_info(vec![fee_token(
+                r#"{"denom":"ujuno","average_gas_price":1e30}"#
+            )])
+            .estimate_fee(1, GasPriceTier::Average, None),
+            Err(Error::InvalidFee { .. })
+        ));
+    }
+}
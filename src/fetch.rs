@@ -0,0 +1,212 @@
+//! Contains the [`Fetcher`] used to download binaries and genesis files, and the sha256
+//! checksums they are verified against.
+use crate::Error;
+use sha2::{Digest, Sha256};
+
+/// Retrieves the contents of a url.
+///
+/// Implemented for closures, so tests and offline setups can serve files from memory or disk:
+///
+/// ```rust
+/// use cosmos_chain_registry::fetch::Fetcher;
+/// use cosmos_chain_registry::Error;
+///
+/// let fetcher = |url: &str| -> Result<Vec<u8>, Error> { Ok(url.as_bytes().to_vec()) };
+/// assert_eq!(fetcher.fetch("hello").unwrap(), b"hello");
+/// ```
+pub trait Fetcher {
+    fn fetch(&self, url: &str) -> Result<Vec<u8>, Error>;
+}
+
+impl<F> Fetcher for F
+where
+    F: Fn(&str) -> Result<Vec<u8>, Error>,
+{
+    fn fetch(&self, url: &str) -> Result<Vec<u8>, Error> {
+        self(url)
+    }
+}
+
+/// Downloads over http and https. Only available with the `http` feature.
+#[cfg(feature = "http")]
+#[derive(Clone, Debug)]
+pub struct HttpFetcher {
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "http")]
+impl Default for HttpFetcher {
+    fn default() -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout_connect(std::time::Duration::from_secs(30))
+                .build(),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl HttpFetcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "http")]
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, url: &str) -> Result<Vec<u8>, Error> {
+        use std::io::Read;
+
+        let failed = |reason: String| Error::Fetch {
+            url: url.to_string(),
+            reason,
+        };
+        let response = self
+            .agent
+            .get(url)
+            .call()
+            .map_err(|e| failed(e.to_string()))?;
+
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut body)
+            .map_err(|e| failed(e.to_string()))?;
+        Ok(body)
+    }
+}
+
+/// A checksum published for a file, e.g. the `sha256:<hex>` of a `?checksum=` url suffix.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Checksum {
+    /// The hash algorithm, only `sha256` can be verified.
+    pub algorithm: String,
+    /// The lower case hex digest.
+    pub hex: String,
+}
+
+impl Checksum {
+    /// A sha256 checksum of the given hex digest.
+    pub fn sha256(hex: &str) -> Self {
+        Self {
+            algorithm: "sha256".to_string(),
+            hex: hex.to_lowercase(),
+        }
+    }
+
+    /// Parses an `<algorithm>:<hex>` checksum.
+    pub fn parse(checksum: &str) -> Option<Self> {
+        let (algorithm, hex) = checksum.split_once(':')?;
+        Some(Self {
+            algorithm: algorithm.to_lowercase(),
+            hex: hex.to_lowercase(),
+        })
+    }
+
+    /// Checks that `bytes` downloaded from `url` match the checksum.
+    pub fn verify(&self, url: &str, bytes: &[u8]) -> Result<(), Error> {
+        if self.algorithm != "sha256" {
+            return Err(Error::Fetch {
+                url: url.to_string(),
+                reason: format!("unsupported checksum algorithm {}", self.algorithm),
+            });
+        }
+
+        let actual = sha256_hex(bytes);
+        if actual == self.hex {
+            Ok(())
+        } else {
+            Err(Error::ChecksumMismatch {
+                url: url.to_string(),
+                expected: self.hex.clone(),
+                actual,
+            })
+        }
+    }
+}
+
+/// The lower case hex sha256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Splits the go-getter style `checksum` query parameter off a url, returning the url without
+/// it and the checksum, e.g. `https://host/junod?checksum=sha256:ab...`.
+pub fn split_checksum(url: &str) -> (String, Option<Checksum>) {
+    let (base, query) = match url.split_once('?') {
+        Some(split) => split,
+        None => return (url.to_string(), None),
+    };
+
+    let mut checksum = None;
+    let rest: Vec<&str> = query
+        .split('&')
+        .filter(|param| match param.strip_prefix("checksum=") {
+            Some(value) => {
+                checksum = Checksum::parse(value);
+                false
+            }
+            None => true,
+        })
+        .collect();
+
+    let url = if rest.is_empty() {
+        base.to_string()
+    } else {
+        format!("{}?{}", base, rest.join("&"))
+    };
+    (url, checksum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_and_verifies_checksums() {
+        let (url, checksum) = split_checksum("https://host/junod?checksum=sha256:ABCD");
+        assert_eq!(url, "https://host/junod");
+        assert_eq!(checksum, Some(Checksum::sha256("abcd")));
+
+        let (url, checksum) = split_checksum("https://host/junod?raw=1&checksum=md5:00&x=2");
+        assert_eq!(url, "https://host/junod?raw=1&x=2");
+        assert_eq!(checksum.unwrap().algorithm, "md5");
+        assert_eq!(split_checksum("https://host/junod").1, None);
+
+        let checksum = Checksum::sha256(&sha256_hex(b"junod"));
+        assert!(checksum.verify("junod", b"junod").is_ok());
+        assert!(matches!(
+            checksum.verify("junod", b"gaiad"),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            Checksum::parse("md5:00").unwrap().verify("junod", b""),
+            Err(Error::Fetch { .. })
+        ));
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn fetches_over_http() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/junod", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\njunod",
+                )
+                .unwrap();
+        });
+
+        assert_eq!(HttpFetcher::new().fetch(&url).unwrap(), b"junod");
+    }
+}
@@ -0,0 +1,356 @@
+//! Contains the retrieval of a chain's genesis file: download, decompression and verification.
+use crate::fetch::{sha256_hex, split_checksum, Fetcher};
+use crate::{ChainInfo, Error};
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+/// The largest decompressed genesis, 4 GiB, which leaves room for the exports of the largest
+/// chains while keeping a hostile or corrupted archive from exhausting memory.
+pub const MAX_GENESIS_SIZE: u64 = 4 << 30;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// A genesis file retrieved by [`fetch`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GenesisFile {
+    /// The url it was downloaded from, without any `checksum` query parameter.
+    pub url: String,
+    /// The decompressed genesis JSON.
+    pub bytes: Vec<u8>,
+    /// The lower case hex sha256 of the decompressed genesis.
+    pub sha256: String,
+    /// `true` if the registry published a checksum for the download, which it matched.
+    pub verified: bool,
+    /// The decompressed interchain security consumer state, if the chain lists an
+    /// `ics_ccv_url`.
+    pub ics_ccv: Option<Vec<u8>>,
+}
+
+impl GenesisFile {
+    /// Writes the genesis JSON to `path`, e.g. `<node_home>/config/genesis.json`.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+        }
+        fs::write(path, &self.bytes).map_err(|e| Error::io(path, e))
+    }
+}
+
+/// Downloads the chain's genesis, decompressing `.gz`, `.tar.gz` and `.zip` archives, and
+/// checks that its `chain_id` is the chain's. The download is checked against the checksum
+/// embedded in `genesis_url`, if any.
+///
+/// Returns [`Error::NotFound`] if the chain lists no genesis, [`Error::ChecksumMismatch`] if
+/// the download does not match its checksum and [`Error::InvalidGenesis`] if it cannot be
+/// decompressed or is for another chain.
+///
+/// ## Example
+///
+/// ```no_run
+/// use cosmos_chain_registry::{genesis, ChainRegistry};
+///
+/// let registry = ChainRegistry::from_path("./chain-registry").unwrap();
+/// let info = registry.get_by_chain_name("juno").unwrap();
+/// # let fetcher = |_: &str| -> Result<Vec<u8>, cosmos_chain_registry::Error> { Ok(Vec::new()) };
+///
+/// // e.g. a `fetch::HttpFetcher` with the `http` feature
+/// let genesis = genesis::fetch(&info, &fetcher).unwrap();
+/// println!("sha256 {}", genesis.sha256);
+/// genesis.write("config/genesis.json").unwrap();
+/// ```
+pub fn fetch(chain_info: &ChainInfo, fetcher: &impl Fetcher) -> Result<GenesisFile, Error> {
+    if chain_info.genesis.genesis_url.is_empty() {
+        return Err(Error::NotFound(format!(
+            "genesis of {}",
+            chain_info.chain_name
+        )));
+    }
+
+    let (url, bytes, verified) = download(fetcher, &chain_info.genesis.genesis_url)?;
+
+    #[derive(Deserialize)]
+    struct GenesisChainId {
+        chain_id: String,
+    }
+    let invalid = |reason: String| Error::InvalidGenesis {
+        url: url.clone(),
+        reason,
+    };
+    let genesis: GenesisChainId =
+        serde_json::from_slice(&bytes).map_err(|e| invalid(e.to_string()))?;
+    if genesis.chain_id != chain_info.chain_id {
+        return Err(invalid(format!(
+            "chain_id is {}, expected {}",
+            genesis.chain_id, chain_info.chain_id
+        )));
+    }
+
+    let ics_ccv = match &chain_info.genesis.ics_ccv_url {
+        Some(ics_ccv_url) => Some(download(fetcher, ics_ccv_url)?.1),
+        None => None,
+    };
+
+    Ok(GenesisFile {
+        sha256: sha256_hex(&bytes),
+        verified,
+        url,
+        bytes,
+        ics_ccv,
+    })
+}
+
+/// Downloads and decompresses a file, checking it against the checksum embedded in its url.
+/// Returns the url without the checksum, the decompressed contents and whether it was checked.
+fn download(fetcher: &impl Fetcher, url: &str) -> Result<(String, Vec<u8>, bool), Error> {
+    let (url, checksum) = split_checksum(url);
+    let downloaded = fetcher.fetch(&url)?;
+    if let Some(checksum) = &checksum {
+        checksum.verify(&url, &downloaded)?;
+    }
+    let bytes = decompress(&url, downloaded)?;
+    Ok((url, bytes, checksum.is_some()))
+}
+
+/// Decompresses a `.gz`, `.tar.gz` or `.zip` download, recognized by its content rather than
+/// its url. The first `.json` file of an archive is taken, preferring `genesis.json`. Plain
+/// files are returned as-is.
+///
+/// Returns [`Error::InvalidGenesis`] if the genesis decompresses to more than
+/// [`MAX_GENESIS_SIZE`] bytes, or if it is gzipped more than once.
+pub fn decompress(url: &str, bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+    decompress_with_limit(url, bytes, MAX_GENESIS_SIZE)
+}
+
+fn decompress_with_limit(url: &str, bytes: Vec<u8>, limit: u64) -> Result<Vec<u8>, Error> {
+    let invalid = |reason: String| Error::InvalidGenesis {
+        url: url.to_string(),
+        reason,
+    };
+    let read = |reader: &mut dyn Read| {
+        let mut contents = Vec::new();
+        reader
+            .take(limit + 1)
+            .read_to_end(&mut contents)
+            .map_err(|e| invalid(e.to_string()))?;
+        if contents.len() as u64 > limit {
+            return Err(invalid(format!(
+                "decompresses to more than {} bytes",
+                limit
+            )));
+        }
+        Ok(contents)
+    };
+
+    let bytes = if bytes.starts_with(GZIP_MAGIC) {
+        let decompressed = read(&mut GzDecoder::new(bytes.as_slice()))?;
+        if decompressed.starts_with(GZIP_MAGIC) {
+            return Err(invalid("gzipped more than once".to_string()));
+        }
+        decompressed
+    } else {
+        bytes
+    };
+
+    if is_tar(&bytes) {
+        let mut archive = tar::Archive::new(bytes.as_slice());
+        let mut found = None;
+        for entry in archive.entries().map_err(|e| invalid(e.to_string()))? {
+            let mut entry = entry.map_err(|e| invalid(e.to_string()))?;
+            let path = entry.path().map_err(|e| invalid(e.to_string()))?;
+            let name = path.to_string_lossy().to_string();
+            if !name.ends_with(".json") || (found.is_some() && !is_genesis_json(&name)) {
+                continue;
+            }
+
+            let contents = read(&mut entry)?;
+            let done = is_genesis_json(&name);
+            found = Some(contents);
+            if done {
+                break;
+            }
+        }
+        return found.ok_or_else(|| invalid("no .json file in the tar archive".to_string()));
+    }
+
+    if bytes.starts_with(b"PK\x03\x04") {
+        let mut archive =
+            zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| invalid(e.to_string()))?;
+        let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        let name = names
+            .iter()
+            .find(|name| is_genesis_json(name))
+            .or_else(|| names.iter().find(|name| name.ends_with(".json")))
+            .ok_or_else(|| invalid("no .json file in the zip archive".to_string()))?;
+
+        let mut file = archive.by_name(name).map_err(|e| invalid(e.to_string()))?;
+        return read(&mut file);
+    }
+
+    Ok(bytes)
+}
+
+/// Tar archives carry the `ustar` magic at offset 257 of their first header.
+fn is_tar(bytes: &[u8]) -> bool {
+    bytes.get(257..262) == Some(b"ustar".as_slice())
+}
+
+fn is_genesis_json(name: &str) -> bool {
+    name == "genesis.json" || name.ends_with("/genesis.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Genesis;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    const GENESIS: &[u8] = br#"{"genesis_time":"2021-10-01T15:00:00Z","chain_id":"juno-1"}"#;
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn tar(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *contents).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            writer
+                .start_file(*name, zip::write::FileOptions::default())
+                .unwrap();
+            writer.write_all(contents).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn chain_info(genesis_url: &str) -> ChainInfo {
+        ChainInfo {
+            chain_name: "juno".to_string(),
+            chain_id: "juno-1".to_string(),
+            genesis: Genesis {
+                genesis_url: genesis_url.to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn decompresses_genesis_archives() {
+        let archives = [
+            GENESIS.to_vec(),
+            gzip(GENESIS),
+            gzip(&tar(&[
+                ("README.json", b"{}"),
+                ("juno/genesis.json", GENESIS),
+            ])),
+            zip(&[("addrbook.json", b"{}"), ("genesis.json", GENESIS)]),
+        ];
+
+        for archive in archives {
+            assert_eq!(decompress("genesis", archive).unwrap(), GENESIS);
+        }
+        assert!(matches!(
+            decompress("genesis", gzip(&tar(&[("README.md", b"")]))),
+            Err(Error::InvalidGenesis { .. })
+        ));
+    }
+
+    #[test]
+    fn limits_the_decompressed_size() {
+        let limit = GENESIS.len() as u64;
+        let padded = [GENESIS, b" "].concat();
+        let archives = [
+            gzip(&padded),
+            gzip(&tar(&[("genesis.json", &padded)])),
+            zip(&[("genesis.json", &padded)]),
+        ];
+
+        for archive in archives {
+            assert!(matches!(
+                decompress_with_limit("genesis", archive, limit),
+                Err(Error::InvalidGenesis { reason, .. }) if reason.contains("more than")
+            ));
+        }
+        assert_eq!(
+            decompress_with_limit("genesis", gzip(GENESIS), limit).unwrap(),
+            GENESIS
+        );
+    }
+
+    #[test]
+    fn rejects_nested_gzip() {
+        assert!(matches!(
+            decompress("genesis", gzip(&gzip(GENESIS))),
+            Err(Error::InvalidGenesis { reason, .. }) if reason.contains("more than once")
+        ));
+    }
+
+    #[test]
+    fn fetches_and_verifies_genesis() {
+        let archive = gzip(&tar(&[("genesis.json", GENESIS)]));
+        let archive_sha256 = sha256_hex(&archive);
+        let fetcher = |url: &str| -> Result<Vec<u8>, Error> {
+            match url {
+                "https://host/genesis.tar.gz" => Ok(archive.clone()),
+                "https://host/ccv.json.gz" => Ok(gzip(b"{\"params\":{}}")),
+                "https://host/other.json" => Ok(br#"{"chain_id":"uni-6"}"#.to_vec()),
+                _ => Err(Error::Fetch {
+                    url: url.to_string(),
+                    reason: "404".to_string(),
+                }),
+            }
+        };
+
+        let mut info = chain_info(&format!(
+            "https://host/genesis.tar.gz?checksum=sha256:{}",
+            archive_sha256
+        ));
+        info.genesis.ics_ccv_url = Some("https://host/ccv.json.gz".to_string());
+        let genesis = fetch(&info, &fetcher).unwrap();
+        assert_eq!(genesis.url, "https://host/genesis.tar.gz");
+        assert_eq!(genesis.bytes, GENESIS);
+        assert_eq!(genesis.sha256, sha256_hex(GENESIS));
+        assert!(genesis.verified);
+        assert_eq!(genesis.ics_ccv.as_deref(), Some(&b"{\"params\":{}}"[..]));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config/genesis.json");
+        genesis.write(&path).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), GENESIS);
+
+        assert!(matches!(
+            fetch(
+                &chain_info("https://host/genesis.tar.gz?checksum=sha256:00"),
+                &fetcher
+            ),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            fetch(&chain_info("https://host/other.json"), &fetcher),
+            Err(Error::InvalidGenesis { .. })
+        ));
+        assert!(matches!(
+            fetch(&chain_info(""), &fetcher),
+            Err(Error::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,567 @@
+//! Contains the health checker probing the rpc, rest and grpc endpoints listed in a chain's
+//! `apis`. Only available with the `health` feature.
+use crate::chain::{ApiKind, Endpoint};
+use crate::ChainInfo;
+use serde_json::Value;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// The default timeout of each probe.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The rest path reporting the node's network.
+pub const REST_NODE_INFO_PATH: &str = "/cosmos/base/tendermint/v1beta1/node_info";
+
+/// The rest path reporting whether the node is syncing.
+pub const REST_SYNCING_PATH: &str = "/cosmos/base/tendermint/v1beta1/syncing";
+
+/// The rest path reporting the node's latest block.
+pub const REST_LATEST_BLOCK_PATH: &str = "/cosmos/base/tendermint/v1beta1/blocks/latest";
+
+/// The most grpc host lookups left running at once after timing out, see [`resolve`].
+const MAX_PENDING_LOOKUPS: usize = 16;
+
+/// The number of grpc host lookups still running.
+static PENDING_LOOKUPS: AtomicUsize = AtomicUsize::new(0);
+
+/// Probes the endpoints of a chain and ranks them.
+///
+/// Rpc endpoints are probed with `/status` and rest endpoints with the tendermint
+/// `node_info`, `syncing` and `blocks/latest` queries. Grpc endpoints are only checked for
+/// reachability, so their network is not verified. Endpoints are probed concurrently.
+///
+/// ## Example
+///
+/// ```no_run
+/// use cosmos_chain_registry::health::HealthChecker;
+/// use cosmos_chain_registry::ChainRegistry;
+///
+/// let registry = ChainRegistry::from_path("./chain-registry").unwrap();
+/// let info = registry.get_by_chain_id("juno-1").unwrap();
+/// let report = HealthChecker::new().check(&info);
+///
+/// for health in report.healthy() {
+///     println!("{} {:?}", health.endpoint.address, health.latency);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct HealthChecker {
+    timeout: Duration,
+}
+
+impl Default for HealthChecker {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+impl HealthChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the timeout of each probe.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Probes every rpc, rest and grpc endpoint of the chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain_info` - The chain whose `apis` are probed, its `chain_id` is the network every
+    ///   endpoint must report.
+    pub fn check(&self, chain_info: &ChainInfo) -> HealthReport {
+        let endpoints = chain_info.apis.all_endpoints();
+        let mut results: Vec<EndpointHealth> = std::thread::scope(|scope| {
+            let probes: Vec<_> = endpoints
+                .into_iter()
+                .map(|endpoint| scope.spawn(|| self.probe(endpoint, &chain_info.chain_id)))
+                .collect();
+            probes
+                .into_iter()
+                .map(|probe| probe.join().expect("endpoint probe panicked"))
+                .collect()
+        });
+        sort_by_health(&mut results);
+
+        HealthReport {
+            chain_id: chain_info.chain_id.clone(),
+            endpoints: results,
+        }
+    }
+
+    /// Probes a single endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The endpoint to probe.
+    /// * `chain_id` - The network the endpoint must report.
+    pub fn probe(&self, endpoint: Endpoint, chain_id: &str) -> EndpointHealth {
+        let mut health = EndpointHealth {
+            endpoint,
+            status: EndpointStatus::Healthy,
+            latency: None,
+            network: None,
+            block_height: None,
+            catching_up: None,
+        };
+
+        let probed = match health.endpoint.kind {
+            ApiKind::Rpc => self.probe_rpc(&mut health),
+            ApiKind::Rest => self.probe_rest(&mut health),
+            ApiKind::Grpc => self.probe_grpc(&mut health),
+        };
+
+        health.status = match probed {
+            Err(reason) => EndpointStatus::Unreachable(reason),
+            Ok(()) => match &health.network {
+                Some(network) if network != chain_id => {
+                    EndpointStatus::WrongNetwork(network.clone())
+                }
+                _ if health.catching_up == Some(true) => EndpointStatus::CatchingUp,
+                _ => EndpointStatus::Healthy,
+            },
+        };
+        health
+    }
+
+    fn probe_rpc(&self, health: &mut EndpointHealth) -> Result<(), String> {
+        let (status, latency) = self.get_json(&health.endpoint.address, "/status")?;
+        health.latency = Some(latency);
+
+        // Older nodes wrap the response in a json-rpc envelope
+        let status = status.get("result").unwrap_or(&status);
+        health.network = string_at(status, "/node_info/network");
+        health.block_height =
+            string_at(status, "/sync_info/latest_block_height").and_then(|h| h.parse().ok());
+        health.catching_up = status
+            .pointer("/sync_info/catching_up")
+            .and_then(Value::as_bool);
+
+        match health.network {
+            Some(_) => Ok(()),
+            None => Err("/status does not report a network".to_string()),
+        }
+    }
+
+    fn probe_rest(&self, health: &mut EndpointHealth) -> Result<(), String> {
+        let address = &health.endpoint.address;
+        let (node_info, latency) = self.get_json(address, REST_NODE_INFO_PATH)?;
+        health.latency = Some(latency);
+        health.network = string_at(&node_info, "/default_node_info/network");
+
+        // Not every node serves these, the endpoint is still usable without them
+        if let Ok((syncing, _)) = self.get_json(address, REST_SYNCING_PATH) {
+            health.catching_up = syncing.get("syncing").and_then(Value::as_bool);
+        }
+        if let Ok((block, _)) = self.get_json(address, REST_LATEST_BLOCK_PATH) {
+            health.block_height = string_at(&block, "/block/header/height")
+                .or_else(|| string_at(&block, "/sdk_block/header/height"))
+                .and_then(|h| h.parse().ok());
+        }
+
+        match health.network {
+            Some(_) => Ok(()),
+            None => Err("node_info does not report a network".to_string()),
+        }
+    }
+
+    fn probe_grpc(&self, health: &mut EndpointHealth) -> Result<(), String> {
+        let host = grpc_host(&health.endpoint.address);
+        let address = resolve(&host, self.timeout)?;
+
+        let start = Instant::now();
+        TcpStream::connect_timeout(&address, self.timeout).map_err(|e| e.to_string())?;
+        health.latency = Some(start.elapsed());
+        Ok(())
+    }
+
+    fn get_json(&self, address: &str, path: &str) -> Result<(Value, Duration), String> {
+        let url = format!("{}{}", address.trim_end_matches('/'), path);
+        let start = Instant::now();
+        let response = ureq::get(&url)
+            .timeout(self.timeout)
+            .call()
+            .map_err(|e| match e {
+                ureq::Error::Status(code, _) => format!("{} returned HTTP {}", path, code),
+                ureq::Error::Transport(transport) => transport.to_string(),
+            })?;
+        let latency = start.elapsed();
+
+        let body = response.into_string().map_err(|e| e.to_string())?;
+        let json = serde_json::from_str(&body).map_err(|e| format!("{}: {}", path, e))?;
+        Ok((json, latency))
+    }
+}
+
+/// The health of the endpoints of a chain, best first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HealthReport {
+    pub chain_id: String,
+    /// Healthy endpoints first, then those catching up, on the wrong network and unreachable,
+    /// each sorted by latency.
+    pub endpoints: Vec<EndpointHealth>,
+}
+
+impl HealthReport {
+    /// The healthy endpoints, fastest first.
+    pub fn healthy(&self) -> impl Iterator<Item = &EndpointHealth> {
+        self.endpoints.iter().filter(|health| health.is_healthy())
+    }
+
+    /// The fastest healthy endpoint of the given kind.
+    pub fn best(&self, kind: ApiKind) -> Option<&EndpointHealth> {
+        self.healthy().find(|health| health.endpoint.kind == kind)
+    }
+
+    /// The highest block height reported by any endpoint.
+    pub fn max_block_height(&self) -> Option<u64> {
+        self.endpoints
+            .iter()
+            .filter_map(|health| health.block_height)
+            .max()
+    }
+}
+
+/// The result of probing an endpoint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EndpointHealth {
+    pub endpoint: Endpoint,
+    pub status: EndpointStatus,
+    /// The response time of the probe, `None` if the endpoint is unreachable.
+    pub latency: Option<Duration>,
+    /// The network reported by the endpoint, always `None` for grpc endpoints.
+    pub network: Option<String>,
+    pub block_height: Option<u64>,
+    pub catching_up: Option<bool>,
+}
+
+impl EndpointHealth {
+    /// Returns `true` if the endpoint is reachable, on the expected network and synced.
+    pub fn is_healthy(&self) -> bool {
+        self.status == EndpointStatus::Healthy
+    }
+}
+
+/// The verdict on an endpoint, from best to worst.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EndpointStatus {
+    Healthy,
+    /// The endpoint is on the expected network but still syncing.
+    CatchingUp,
+    /// The endpoint reports another network, which is held.
+    WrongNetwork(String),
+    /// The probe failed for the held reason.
+    Unreachable(String),
+}
+
+impl EndpointStatus {
+    fn rank(&self) -> u8 {
+        match self {
+            EndpointStatus::Healthy => 0,
+            EndpointStatus::CatchingUp => 1,
+            EndpointStatus::WrongNetwork(_) => 2,
+            EndpointStatus::Unreachable(_) => 3,
+        }
+    }
+}
+
+fn string_at(json: &Value, pointer: &str) -> Option<String> {
+    json.pointer(pointer)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Sorts endpoints by status, then by latency with unmeasured endpoints after the measured ones
+/// of the same status.
+fn sort_by_health(endpoints: &mut [EndpointHealth]) {
+    endpoints.sort_by_key(|health| {
+        (
+            health.status.rank(),
+            health.latency.is_none(),
+            health.latency,
+        )
+    });
+}
+
+/// The `host:port` of a grpc address, which the registry lists with or without a scheme.
+/// Addresses without a port use 443 for `https` and 9090, the cosmos-sdk default, otherwise.
+/// IPv6 addresses have a port only after their closing `]`, and are bracketed if they are not.
+fn grpc_host(address: &str) -> String {
+    let (tls, rest) = match address.split_once("://") {
+        Some((scheme, rest)) => (scheme == "https", rest),
+        None => (false, address),
+    };
+    let host = rest.split('/').next().unwrap_or_default();
+
+    let has_port = match host.rsplit_once(']') {
+        Some((_, port)) => port.starts_with(':'),
+        None => host.matches(':').count() == 1,
+    };
+    if has_port {
+        return host.to_string();
+    }
+
+    let host = if host.contains(':') && !host.starts_with('[') {
+        format!("[{}]", host)
+    } else {
+        host.to_string()
+    };
+    if tls {
+        format!("{}:443", host)
+    } else {
+        format!("{}:9090", host)
+    }
+}
+
+/// Resolves `host` to its first socket address. The lookup runs on its own thread as the
+/// system resolver cannot be given a timeout, and is abandoned once `timeout` elapses.
+///
+/// An abandoned lookup keeps its thread until the resolver returns. At most
+/// [`MAX_PENDING_LOOKUPS`] lookups run at once, further ones fail until some of them finish.
+fn resolve(host: &str, timeout: Duration) -> Result<SocketAddr, String> {
+    let pending = PendingLookup::start()?;
+    let (sender, receiver) = mpsc::channel();
+    let lookup = host.to_string();
+    std::thread::spawn(move || {
+        let address = lookup
+            .to_socket_addrs()
+            .map(|mut addresses| addresses.next());
+        let _ = sender.send(address);
+        drop(pending);
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(Some(address))) => Ok(address),
+        Ok(Ok(None)) => Err(format!("{} does not resolve", host)),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("resolving {} timed out", host)),
+    }
+}
+
+/// Counts a running lookup in [`PENDING_LOOKUPS`] until it is dropped.
+struct PendingLookup;
+
+impl PendingLookup {
+    fn start() -> Result<Self, String> {
+        PENDING_LOOKUPS
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |pending| {
+                (pending < MAX_PENDING_LOOKUPS).then_some(pending + 1)
+            })
+            .map(|_| PendingLookup)
+            .map_err(|_| format!("{} host lookups are already pending", MAX_PENDING_LOOKUPS))
+    }
+}
+
+impl Drop for PendingLookup {
+    fn drop(&mut self) {
+        PENDING_LOOKUPS.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::{Apis, Grpc, Rest, Rpc};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Serves `routes` as json over plain http until the test exits.
+    fn mock_server(routes: Vec<(&'static str, Value)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let response = match routes.iter().find(|(route, _)| *route == path) {
+                    Some((_, body)) => {
+                        let body = body.to_string();
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            body.len(),
+                            body
+                        )
+                    }
+                    None => {
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_string()
+                    }
+                };
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        address
+    }
+
+    fn rpc_status(network: &str, height: &str, catching_up: bool) -> Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": -1,
+            "result": {
+                "node_info": { "network": network },
+                "sync_info": { "latest_block_height": height, "catching_up": catching_up }
+            }
+        })
+    }
+
+    #[test]
+    fn probes_and_ranks_endpoints() {
+        let healthy_rpc = mock_server(vec![("/status", rpc_status("juno-1", "1200", false))]);
+        let syncing_rpc = mock_server(vec![("/status", rpc_status("juno-1", "900", true))]);
+        let rest = mock_server(vec![
+            (
+                REST_NODE_INFO_PATH,
+                serde_json::json!({ "default_node_info": { "network": "uni-6" } }),
+            ),
+            (REST_SYNCING_PATH, serde_json::json!({ "syncing": false })),
+            (
+                REST_LATEST_BLOCK_PATH,
+                serde_json::json!({ "block": { "header": { "height": "42" } } }),
+            ),
+        ]);
+        let grpc = mock_server(Vec::new()).replace("http://", "");
+
+        // A port nobody listens on
+        let closed = TcpListener::bind("127.0.0.1:0").unwrap();
+        let dead_rpc = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+
+        let info = ChainInfo {
+            chain_id: "juno-1".to_string(),
+            apis: Apis {
+                rpc: [&dead_rpc, &syncing_rpc, &healthy_rpc]
+                    .into_iter()
+                    .map(|address| Rpc {
+                        address: address.clone(),
+                        provider: None,
+                    })
+                    .collect(),
+                rest: vec![Rest {
+                    address: rest,
+                    provider: Some("other".to_string()),
+                }],
+                grpc: vec![Grpc {
+                    address: grpc.clone(),
+                    provider: None,
+                }],
+            },
+            ..Default::default()
+        };
+
+        let report = HealthChecker::new()
+            .timeout(Duration::from_secs(2))
+            .check(&info);
+        assert_eq!(report.chain_id, "juno-1");
+        assert_eq!(report.endpoints.len(), 5);
+
+        let best_rpc = report.best(ApiKind::Rpc).unwrap();
+        assert_eq!(best_rpc.endpoint.address, healthy_rpc);
+        assert_eq!(best_rpc.block_height, Some(1200));
+        assert!(best_rpc.latency.is_some());
+
+        let best_grpc = report.best(ApiKind::Grpc).unwrap();
+        assert_eq!(best_grpc.endpoint.address, grpc);
+        assert_eq!(best_grpc.network, None);
+        assert!(report.best(ApiKind::Rest).is_none());
+        assert_eq!(report.healthy().count(), 2);
+
+        let statuses: Vec<&EndpointStatus> = report.endpoints[2..]
+            .iter()
+            .map(|health| &health.status)
+            .collect();
+        assert_eq!(statuses[0], &EndpointStatus::CatchingUp);
+        assert_eq!(
+            statuses[1],
+            &EndpointStatus::WrongNetwork("uni-6".to_string())
+        );
+        assert!(matches!(statuses[2], EndpointStatus::Unreachable(_)));
+        assert_eq!(report.endpoints[3].block_height, Some(42));
+        assert_eq!(report.max_block_height(), Some(1200));
+    }
+
+    #[test]
+    fn parses_grpc_hosts() {
+        assert_eq!(grpc_host("grpc.juno.example:443"), "grpc.juno.example:443");
+        assert_eq!(
+            grpc_host("https://grpc.juno.example"),
+            "grpc.juno.example:443"
+        );
+        assert_eq!(
+            grpc_host("http://grpc.juno.example/"),
+            "grpc.juno.example:9090"
+        );
+        assert_eq!(grpc_host("grpc.juno.example"), "grpc.juno.example:9090");
+
+        // IPv6 addresses only have a port after their closing bracket
+        assert_eq!(grpc_host("[::1]:9091"), "[::1]:9091");
+        assert_eq!(grpc_host("https://[::1]"), "[::1]:443");
+        assert_eq!(grpc_host("2001:db8::1"), "[2001:db8::1]:9090");
+    }
+
+    #[test]
+    fn ranks_unmeasured_endpoints_last() {
+        let health = |address: &str, latency: Option<u64>| EndpointHealth {
+            endpoint: Endpoint {
+                kind: ApiKind::Rpc,
+                address: address.to_string(),
+                provider: None,
+            },
+            status: EndpointStatus::Healthy,
+            latency: latency.map(Duration::from_millis),
+            network: None,
+            block_height: None,
+            catching_up: None,
+        };
+        let mut endpoints = vec![
+            health("unmeasured", None),
+            health("slow", Some(300)),
+            health("fast", Some(20)),
+        ];
+        sort_by_health(&mut endpoints);
+
+        let order: Vec<&str> = endpoints
+            .iter()
+            .map(|health| health.endpoint.address.as_str())
+            .collect();
+        assert_eq!(order, ["fast", "slow", "unmeasured"]);
+    }
+
+    #[test]
+    fn resolves_grpc_hosts() {
+        let timeout = Duration::from_secs(5);
+        assert_eq!(
+            resolve("127.0.0.1:9090", timeout).unwrap(),
+            "127.0.0.1:9090".parse().unwrap()
+        );
+        assert!(resolve("missing-port", timeout).is_err());
+
+        assert_eq!(
+            resolve(&grpc_host("[::1]"), timeout).unwrap(),
+            "[::1]:9090".parse().unwrap()
+        );
+        assert_eq!(
+            resolve(&grpc_host("2001:db8::1"), timeout).unwrap(),
+            "[2001:db8::1]:9090".parse().unwrap()
+        );
+    }
+}
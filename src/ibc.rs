@@ -0,0 +1,166 @@
+//! Contains models for serializing and deserializing the files in the `_IBC` directory of the registry repository,
+//! which describe the clients, connections and channels between a pair of chains.
+use crate::chain::ChainStatus;
+use serde::{Deserialize, Serialize};
+
+/// The registry directory holding the IBC connection files, both at the root and in `testnets`.
+pub const IBC_DIR: &str = "_IBC";
+
+/// The port used by ICS-20 fungible token transfers.
+pub const TRANSFER_PORT: &str = "transfer";
+
+/// The IBC connection between two chains and the channels opened on it.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct IbcData {
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub chain_1: IbcChain,
+    pub chain_2: IbcChain,
+    pub channels: Vec<IbcChannel>,
+}
+
+impl IbcData {
+    /// Returns `true` if this connection is between `chain_a` and `chain_b`, in either order.
+    pub fn connects(&self, chain_a: &str, chain_b: &str) -> bool {
+        (self.chain_1.chain_name == chain_a && self.chain_2.chain_name == chain_b)
+            || (self.chain_1.chain_name == chain_b && self.chain_2.chain_name == chain_a)
+    }
+
+    /// The channels of this connection as seen from `chain_name`, which must be one of the two
+    /// connected chains. Returns `None` otherwise.
+    pub fn channels_from(&self, chain_name: &str) -> Option<Vec<ChannelInfo>> {
+        let flipped = if self.chain_1.chain_name == chain_name {
+            false
+        } else if self.chain_2.chain_name == chain_name {
+            true
+        } else {
+            return None;
+        };
+
+        let (chain, counterparty) = if flipped {
+            (&self.chain_2, &self.chain_1)
+        } else {
+            (&self.chain_1, &self.chain_2)
+        };
+
+        let channels = self
+            .channels
+            .iter()
+            .map(|channel| {
+                let (end, counterparty_end) = if flipped {
+                    (&channel.chain_2, &channel.chain_1)
+                } else {
+                    (&channel.chain_1, &channel.chain_2)
+                };
+
+                ChannelInfo {
+                    chain_name: chain.chain_name.clone(),
+                    client_id: chain.client_id.clone(),
+                    connection_id: chain.connection_id.clone(),
+                    channel_id: end.channel_id.clone(),
+                    port_id: end.port_id.clone(),
+                    counterparty_chain_name: counterparty.chain_name.clone(),
+                    counterparty_client_id: counterparty.client_id.clone(),
+                    counterparty_connection_id: counterparty.connection_id.clone(),
+                    counterparty_channel_id: counterparty_end.channel_id.clone(),
+                    counterparty_port_id: counterparty_end.port_id.clone(),
+                    ordering: channel.ordering.clone(),
+                    version: channel.version.clone(),
+                    status: channel.tags.as_ref().and_then(|tags| tags.status.clone()),
+                    preferred: channel
+                        .tags
+                        .as_ref()
+                        .and_then(|tags| tags.preferred)
+                        .unwrap_or(false),
+                }
+            })
+            .collect();
+
+        Some(channels)
+    }
+}
+
+/// One side of an IBC connection.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct IbcChain {
+    pub chain_name: String,
+    pub client_id: String,
+    pub connection_id: String,
+}
+
+/// A channel opened on an IBC connection.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct IbcChannel {
+    pub chain_1: ChannelEnd,
+    pub chain_2: ChannelEnd,
+    /// Either `ordered` or `unordered`.
+    pub ordering: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<ChannelTags>,
+}
+
+/// One side of an IBC channel.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ChannelEnd {
+    pub channel_id: String,
+    pub port_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ChannelTags {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ChainStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dex: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub properties: Option<String>,
+}
+
+/// An IBC channel as seen from one of the chains it connects, with the ids of the
+/// counterparty chain's end.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ChannelInfo {
+    pub chain_name: String,
+    pub client_id: String,
+    pub connection_id: String,
+    pub channel_id: String,
+    pub port_id: String,
+    pub counterparty_chain_name: String,
+    pub counterparty_client_id: String,
+    pub counterparty_connection_id: String,
+    pub counterparty_channel_id: String,
+    pub counterparty_port_id: String,
+    pub ordering: String,
+    pub version: String,
+    pub status: Option<ChainStatus>,
+    pub preferred: bool,
+}
+
+impl ChannelInfo {
+    /// Returns `true` if both ends of the channel are bound to the ICS-20 `transfer` port.
+    pub fn is_transfer(&self) -> bool {
+        self.port_id == TRANSFER_PORT && self.counterparty_port_id == TRANSFER_PORT
+    }
+
+    /// Returns `true` if the registry tags the channel as `live`.
+    pub fn is_live(&self) -> bool {
+        matches!(self.status, Some(ChainStatus::Live))
+    }
+
+    /// Returns `true` if the registry tags the channel as `killed`.
+    pub fn is_killed(&self) -> bool {
+        matches!(self.status, Some(ChainStatus::Killed))
+    }
+}
@@ -0,0 +1,342 @@
+//! Contains the in-memory index of a registry directory, built once when a
+//! [`ChainRegistry`](crate::ChainRegistry) is loaded so lookups do not touch the filesystem.
+use crate::ibc::{IbcData, IBC_DIR};
+use crate::list::{ChainKind, ChainListOptions, DEVNETS_DIR, NON_COSMOS_DIR, TESTNETS_DIR};
+use crate::{AssetList, ChainInfo, Error};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::warn;
+
+/// All chains of a registry directory, indexed by the fields they are looked up by.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RegistryIndex {
+    chains: Vec<ChainInfo>,
+    paths: Vec<PathBuf>,
+    kinds: Vec<ChainKind>,
+    by_chain_id: HashMap<String, Vec<usize>>,
+    by_chain_name: HashMap<String, Vec<usize>>,
+    by_directory: HashMap<String, usize>,
+    by_bech32_prefix: HashMap<String, Vec<usize>>,
+    /// Asset lists keyed by the chain_name of the chain directory they are in, with their path.
+    assets: HashMap<String, Vec<(PathBuf, AssetList)>>,
+    ibc: Vec<IbcData>,
+    /// Files that failed to parse, with the raw JSON they contain when it could be read.
+    failures: Vec<ParseFailure>,
+    asset_failures: HashMap<String, ParseFailure>,
+    ibc_failures: Vec<ParseFailure>,
+}
+
+#[derive(Clone, Debug)]
+struct ParseFailure {
+    path: PathBuf,
+    directory: String,
+    value: Option<Arc<serde_json::Value>>,
+    source: Arc<serde_json::Error>,
+}
+
+impl ParseFailure {
+    /// The string at the JSON `pointer` in the raw file, e.g. `/chain_id`.
+    fn key(&self, pointer: &str) -> Option<&str> {
+        self.value.as_ref()?.pointer(pointer)?.as_str()
+    }
+
+    fn in_directory(self, directory: &str) -> Self {
+        Self {
+            directory: directory.to_string(),
+            ..self
+        }
+    }
+
+    fn to_error(&self) -> Error {
+        Error::Parse {
+            path: self.path.clone(),
+            source: self.source.clone(),
+        }
+    }
+}
+
+impl RegistryIndex {
+    /// Reads and parses the `chain.json` and `assetlist.json` of every chain directory under
+    /// `path`. Files that fail to parse are recorded and reported when the chain they declare is
+    /// looked up.
+    pub(crate) fn load(path: &Path) -> Result<Self, Error> {
+        let mut index = Self::default();
+
+        for dir in chain_dirs(path)? {
+            let directory = relative_dir(path, &dir);
+
+            let file = dir.join("chain.json");
+            let mut chain_name = None;
+            if file.is_file() {
+                match read_json::<ChainInfo>(&file)? {
+                    Ok(chain_info) => {
+                        chain_name = Some(chain_info.chain_name.clone());
+                        index.insert(file, directory.clone(), chain_info);
+                    }
+                    Err(failure) => {
+                        chain_name = failure.key("/chain_name").map(str::to_string);
+                        index.failures.push(failure.in_directory(&directory));
+                    }
+                }
+            }
+
+            let file = dir.join("assetlist.json");
+            if file.is_file() {
+                match read_json::<AssetList>(&file)? {
+                    Ok(asset_list) => {
+                        let chain_name =
+                            chain_name.unwrap_or_else(|| asset_list.chain_name.clone());
+                        index
+                            .assets
+                            .entry(chain_name)
+                            .or_default()
+                            .push((file, asset_list));
+                    }
+                    Err(failure) => {
+                        let chain_name = chain_name
+                            .or_else(|| failure.key("/chain_name").map(str::to_string))
+                            .unwrap_or_else(|| directory.clone());
+                        index
+                            .asset_failures
+                            .insert(chain_name, failure.in_directory(&directory));
+                    }
+                }
+            }
+        }
+
+        for dir in ibc_dirs(path) {
+            for file in glob_files(&dir, "*.json")? {
+                match read_json::<IbcData>(&file)? {
+                    Ok(ibc_data) => index.ibc.push(ibc_data),
+                    Err(failure) => index.ibc_failures.push(failure),
+                }
+            }
+        }
+
+        Ok(index)
+    }
+
+    fn insert(&mut self, path: PathBuf, directory: String, chain_info: ChainInfo) {
+        let idx = self.chains.len();
+
+        self.by_chain_id
+            .entry(chain_info.chain_id.clone())
+            .or_default()
+            .push(idx);
+        self.by_chain_name
+            .entry(chain_info.chain_name.clone())
+            .or_default()
+            .push(idx);
+        self.kinds
+            .push(ChainKind::classify(&directory, &chain_info));
+        self.by_directory.insert(directory, idx);
+        self.by_bech32_prefix
+            .entry(chain_info.bech32_prefix.clone())
+            .or_default()
+            .push(idx);
+        self.chains.push(chain_info);
+        self.paths.push(path);
+    }
+
+    pub(crate) fn chains(&self, options: ChainListOptions) -> impl Iterator<Item = &ChainInfo> {
+        self.chains
+            .iter()
+            .zip(&self.kinds)
+            .filter(move |(_, kind)| options.includes(**kind))
+            .map(|(chain_info, _)| chain_info)
+    }
+
+    pub(crate) fn by_chain_id(&self, chain_id: &str) -> Result<&ChainInfo, Error> {
+        match self.by_chain_id.get(chain_id).map(Vec::as_slice) {
+            Some([idx]) => Ok(&self.chains[*idx]),
+            Some(idxs) => Err(Error::DuplicateChainId {
+                chain_id: chain_id.to_string(),
+                paths: idxs.iter().map(|idx| self.paths[*idx].clone()).collect(),
+            }),
+            None => Err(self.missing(chain_id, |failure| {
+                failure.key("/chain_id") == Some(chain_id)
+            })),
+        }
+    }
+
+    pub(crate) fn by_chain_name(&self, chain_name: &str) -> Result<&ChainInfo, Error> {
+        match self.by_chain_name.get(chain_name).map(Vec::as_slice) {
+            Some([idx]) => Ok(&self.chains[*idx]),
+            Some(idxs) => Err(Error::DuplicateChainName {
+                chain_name: chain_name.to_string(),
+                paths: idxs.iter().map(|idx| self.paths[*idx].clone()).collect(),
+            }),
+            None => Err(self.missing(chain_name, |failure| {
+                failure.key("/chain_name") == Some(chain_name)
+            })),
+        }
+    }
+
+    pub(crate) fn by_directory(&self, directory: &str) -> Result<&ChainInfo, Error> {
+        let directory = directory.trim_start_matches("./").trim_end_matches('/');
+        match self.by_directory.get(directory) {
+            Some(idx) => Ok(&self.chains[*idx]),
+            None => Err(self.missing(directory, |failure| failure.directory == directory)),
+        }
+    }
+
+    pub(crate) fn assets(&self, chain_name: &str) -> Result<&AssetList, Error> {
+        match self.assets.get(chain_name).map(Vec::as_slice) {
+            Some([(_, asset_list)]) => Ok(asset_list),
+            Some(asset_lists) => Err(Error::DuplicateAssetList {
+                chain_name: chain_name.to_string(),
+                paths: asset_lists.iter().map(|(path, _)| path.clone()).collect(),
+            }),
+            None => Err(self
+                .asset_failures
+                .get(chain_name)
+                .map(ParseFailure::to_error)
+                .unwrap_or_else(|| Error::NotFound(format!("asset list of {}", chain_name)))),
+        }
+    }
+
+    pub(crate) fn ibc_data(&self) -> &[IbcData] {
+        &self.ibc
+    }
+
+    pub(crate) fn ibc(&self, chain_a: &str, chain_b: &str) -> Result<&IbcData, Error> {
+        self.ibc
+            .iter()
+            .find(|ibc_data| ibc_data.connects(chain_a, chain_b))
+            .ok_or_else(|| {
+                self.ibc_failures
+                    .iter()
+                    .find(|failure| {
+                        let names = (
+                            failure.key("/chain_1/chain_name"),
+                            failure.key("/chain_2/chain_name"),
+                        );
+                        names == (Some(chain_a), Some(chain_b))
+                            || names == (Some(chain_b), Some(chain_a))
+                    })
+                    .map(ParseFailure::to_error)
+                    .unwrap_or_else(|| {
+                        Error::NotFound(format!("IBC data for {}-{}", chain_a, chain_b))
+                    })
+            })
+    }
+
+    /// The error for a key that is not indexed: the parse error of the file declaring it if
+    /// there is one, [`Error::NotFound`] otherwise.
+    fn missing(&self, key: &str, declares: impl Fn(&ParseFailure) -> bool) -> Error {
+        self.failures
+            .iter()
+            .find(|failure| declares(failure))
+            .map(ParseFailure::to_error)
+            .unwrap_or_else(|| Error::NotFound(format!("chain {}", key)))
+    }
+
+    pub(crate) fn by_bech32_prefix(&self, prefix: &str) -> impl Iterator<Item = &ChainInfo> {
+        self.by_bech32_prefix
+            .get(prefix)
+            .into_iter()
+            .flatten()
+            .map(|idx| &self.chains[*idx])
+    }
+}
+
+/// Reads `file` and parses it into `T`. Parse failures are returned as the inner error, along
+/// with the raw JSON when the file is valid JSON at all.
+fn read_json<T: DeserializeOwned>(file: &Path) -> Result<Result<T, ParseFailure>, Error> {
+    let contents = std::fs::read(file).map_err(|e| Error::io(file, e))?;
+
+    Ok(serde_json::from_slice::<T>(&contents).map_err(|e| {
+        warn!("Failed to parse {}: {}", file.display(), e);
+        // Keep the raw JSON if possible so lookups can tell a broken chain apart from a
+        // missing one
+        let value = serde_json::from_slice::<serde_json::Value>(&contents).ok();
+
+        ParseFailure {
+            path: file.to_path_buf(),
+            directory: String::new(),
+            value: value.map(Arc::new),
+            source: Arc::new(e),
+        }
+    }))
+}
+
+/// Returns the chain directories of the registry at `root`, sorted by path: every top level
+/// directory, plus the directories inside the `testnets`, `devnets` and `_non-cosmos`
+/// sections. Hidden directories and underscore prefixed helper directories such as `_template`
+/// and `_IBC` are skipped.
+pub(crate) fn chain_dirs(root: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut dirs = Vec::new();
+
+    for dir in sub_dirs(root)? {
+        let name = dir.file_name().unwrap_or_default().to_string_lossy();
+        if [TESTNETS_DIR, DEVNETS_DIR, NON_COSMOS_DIR].contains(&name.as_ref()) {
+            dirs.extend(
+                sub_dirs(&dir)?
+                    .into_iter()
+                    .filter(|dir| !is_helper_dir(dir)),
+            );
+        } else if !is_helper_dir(&dir) {
+            dirs.push(dir);
+        }
+    }
+
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Returns the `_IBC` directories of the registry at `root`: the mainnet one and those of the
+/// `testnets` and `devnets` sections, when they exist.
+pub(crate) fn ibc_dirs(root: &Path) -> Vec<PathBuf> {
+    [
+        root.to_path_buf(),
+        root.join(TESTNETS_DIR),
+        root.join(DEVNETS_DIR),
+    ]
+    .into_iter()
+    .map(|section| section.join(IBC_DIR))
+    .filter(|dir| dir.is_dir())
+    .collect()
+}
+
+/// Returns the directories directly inside `dir`.
+fn sub_dirs(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut dirs = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(|e| Error::io(dir, e))? {
+        let path = entry.map_err(|e| Error::io(dir, e))?.path();
+        if path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    Ok(dirs)
+}
+
+fn is_helper_dir(dir: &Path) -> bool {
+    let name = dir.file_name().unwrap_or_default().to_string_lossy();
+    name.starts_with('_') || name.starts_with('.')
+}
+
+/// Returns the files under `root` matching the glob `pattern`. The root itself is escaped so
+/// directories containing glob metacharacters are matched literally.
+pub(crate) fn glob_files(root: &Path, pattern: &str) -> Result<Vec<PathBuf>, Error> {
+    let root_pattern = glob::Pattern::escape(&root.to_string_lossy());
+    let paths = glob::glob(&format!("{}/{}", root_pattern, pattern)).map_err(|e| {
+        Error::InvalidRegistry {
+            path: root.to_path_buf(),
+            reason: e.to_string(),
+        }
+    })?;
+
+    Ok(paths.collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Returns `dir` relative to the registry `root` with `/` separators, e.g. `testnets/junotestnet`.
+pub(crate) fn relative_dir(root: &Path, dir: &Path) -> String {
+    dir.strip_prefix(root)
+        .unwrap_or(dir)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
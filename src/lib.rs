@@ -3,7 +3,7 @@
 //!
 //! ## Example
 //!
-//! ```rust
+//! ```no_run
 //! use cosmos_chain_registry::ChainRegistry;
 //!
 //! let registry = ChainRegistry::from_remote().unwrap();
@@ -14,121 +14,1062 @@
 //! assert_eq!(info.pretty_name, "Juno");
 //! ```
 //!
+pub use assetlist::{Asset, AssetList, DenomUnit};
+pub use builder::{ChainRegistryBuilder, FetchPolicy, GitAuth};
 pub use chain::ChainInfo;
-use git2::FetchOptions;
-use lazy_static::lazy_static;
-use std::path::PathBuf;
-use tracing::{debug, info};
+use denom::ResolvedDenom;
+pub use diagnostics::RegistryDiagnostics;
+pub use error::Error;
+pub use ibc::{ChannelInfo, IbcData};
+use index::RegistryIndex;
+use list::ChainListOptions;
+use pfm::ForwardTransferBuilder;
+use query::ChainQuery;
+use route::{ChannelGraph, IbcRoute, RouteOptions};
+use std::path::{Path, PathBuf};
+use tracing::debug;
 
+pub mod assetlist;
+pub mod binary;
+pub mod builder;
 pub mod chain;
+pub mod denom;
+pub mod diagnostics;
+pub mod error;
+pub mod explorer;
+pub mod export;
+pub mod fee;
+pub mod fetch;
+pub mod genesis;
+#[cfg(feature = "health")]
+pub mod health;
+pub mod ibc;
+mod index;
+pub mod list;
+pub mod pfm;
+pub mod query;
+pub mod route;
+pub mod selector;
 
-/// Generic error type for this crate
-pub type Error = Box<dyn std::error::Error>;
+/// The git url of the upstream chain registry.
+#[deprecated(
+    note = "use `builder::DEFAULT_CHAIN_REGISTRY_URL`, or `ChainRegistryBuilder::url` to override it"
+)]
+pub const GITHUB_CHAIN_REGISTRY_URL: &str = builder::DEFAULT_CHAIN_REGISTRY_URL;
 
-lazy_static! {
-    /// The git url for the chain registry to clone. This is the default url, but can be overridden by
-    /// setting the `CHAIN_REGISTRY_URL` environment variable.
-    pub static ref GITHUB_CHAIN_REGISTRY_URL: String = std::env::var("GITHUB_CHAIN_REGISTRY_URL")
-        .unwrap_or_else(|_| { "https://github.com/cosmos/chain-registry".to_string() });
-
-    /// The git ref to checkout. This is the default ref, but can be overridden by setting the
-    /// `CHAIN_REGISTRY_REF` environment variable.
-    pub static ref GITHUB_CHAIN_REGISTRY_REF: String =
-        std::env::var("GITHUB_CHAIN_REGISTRY_REF").unwrap_or_else(|_| { "master".to_string() });
-}
+/// The git ref of the upstream chain registry that is checked out.
+#[deprecated(
+    note = "use `builder::DEFAULT_CHAIN_REGISTRY_REF`, or `ChainRegistryBuilder::git_ref` to override it"
+)]
+pub const GITHUB_CHAIN_REGISTRY_REF: &str = builder::DEFAULT_CHAIN_REGISTRY_REF;
 
 /// The `ChainRegistry` struct is used to fetch and parse chain information from the
 /// [Cosmos Chain Registry](https://github.com/cosmos/chain-registry).
+///
+/// The registry is parsed once when it is created, lookups are served from memory until
+/// [`ChainRegistry::reload`] is called.
+#[derive(Clone, Debug)]
 pub struct ChainRegistry {
     path: PathBuf,
+    index: RegistryIndex,
 }
 
 impl ChainRegistry {
-    /// Creates a new `ChainRegistry` instance. The `path` argument is the path to the
-    /// local clone of the [Cosmos Chain Registry](https://github.com/cosmos/chain-registry).
+    /// Creates a new `ChainRegistry` instance from the upstream
+    /// [Cosmos Chain Registry](https://github.com/cosmos/chain-registry), cloning it into the
+    /// default cache directory or updating an existing clone there.
+    ///
+    /// Use [`ChainRegistry::builder`] to configure the remote, ref, cache directory or fetch policy.
     pub fn from_remote() -> Result<Self, Error> {
-        // Store the chain registry in a local hidden directory
-        let pwd = std::env::current_dir()?;
-        let repo_path = pwd.join(".cosmos-chain-registry");
-
-        if !repo_path.exists() {
-            info!(
-                "Cloning chain registry from {} to {}",
-                GITHUB_CHAIN_REGISTRY_URL.as_str(),
-                repo_path.display()
-            );
+        ChainRegistryBuilder::new().build()
+    }
+
+    /// Creates a [`ChainRegistryBuilder`] with the default settings.
+    pub fn builder() -> ChainRegistryBuilder {
+        ChainRegistryBuilder::new()
+    }
+
+    /// Creates a new `ChainRegistry` instance from an existing local directory, without any
+    /// git or network activity. The directory can be a plain copy of the registry or a vendored
+    /// checkout (e.g. a git submodule), it is read as-is and never fetched or checked out.
+    ///
+    /// Returns an error if the directory does not look like a chain registry, i.e. it does not
+    /// exist or contains no `<chain>/chain.json` files.
+    ///
+    /// # Arguments
+    ///
+    /// `path` - The path to the local copy of the [Cosmos Chain Registry](https://github.com/cosmos/chain-registry).
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+
+        if !path.is_dir() {
+            return Err(Error::InvalidRegistry {
+                path: path.to_path_buf(),
+                reason: "directory does not exist".to_string(),
+            });
         }
 
-        // Try to clone the repo
-        match git2::Repository::clone(GITHUB_CHAIN_REGISTRY_URL.as_str(), &repo_path) {
-            Err(e) => match e.code() {
-                // If the repo already exists, pull the latest changes
-                git2::ErrorCode::Exists => {
-                    debug!("Chain registry already exists, pulling latest changes");
-                    // Get the repo
-                    let repo = git2::Repository::open(&repo_path)?;
-                    // Get the remote
-                    let mut remote = repo.find_remote("origin")?;
-
-                    // Fetch the latest changes
-                    let mut fo = FetchOptions::new();
-                    remote.fetch(&[GITHUB_CHAIN_REGISTRY_REF.as_str()], Some(&mut fo), None)?;
-
-                    // Checkout the latest changes
-                    let (object, reference) = repo.revparse_ext(&GITHUB_CHAIN_REGISTRY_REF)?;
-                    repo.checkout_tree(&object, None)?;
-                    match reference {
-                        Some(gref) => repo.set_head(gref.name().unwrap()),
-                        None => repo.set_head_detached(object.id()),
-                    }?;
-                }
-                _ => return Err(e.into()),
-            },
-            Ok(_) => (),
-        };
-
-        let registry = Self { path: repo_path };
-        Ok(registry)
+        // A registry has at least one top level chain directory containing a chain.json
+        if index::glob_files(path, "*/chain.json")?.is_empty() {
+            return Err(Error::InvalidRegistry {
+                path: path.to_path_buf(),
+                reason: "no */chain.json files found".to_string(),
+            });
+        }
+
+        debug!("Using local chain registry at {}", path.display());
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            index: RegistryIndex::load(path)?,
+        })
+    }
+
+    /// Re-reads the registry directory and rebuilds the in-memory index, e.g. after the
+    /// directory has been updated out of band.
+    pub fn reload(&mut self) -> Result<(), Error> {
+        self.index = RegistryIndex::load(&self.path)?;
+        Ok(())
+    }
+
+    /// Parses every known file of every chain directory in the registry and reports, per
+    /// directory, whether each file parsed, the serde error if it did not, and the fields it
+    /// contains that are not modeled by this crate. Reads from disk on every call.
+    pub fn diagnostics(&self) -> Result<RegistryDiagnostics, Error> {
+        diagnostics::diagnose(&self.path)
+    }
+
+    /// Iterates over every Cosmos chain in the registry, mainnets, testnets and devnets, sorted by
+    /// directory. `_non-cosmos` entries and the registry's `_template` are not included.
+    pub fn chains(&self) -> impl Iterator<Item = &ChainInfo> {
+        self.index.chains(ChainListOptions::default())
+    }
+
+    /// Iterates over the chains in the registry selected by `options`, sorted by directory.
+    ///
+    /// # Arguments
+    ///
+    /// `options` - Which kinds of chains to include, see [`ChainListOptions`].
+    pub fn chains_with(&self, options: ChainListOptions) -> impl Iterator<Item = &ChainInfo> {
+        self.index.chains(options)
+    }
+
+    /// Iterates over the Cosmos chains in the registry matching `query`, sorted by directory.
+    /// Combine [`ChainRegistry::chains_with`] with [`ChainQuery::matches`] to query other kinds
+    /// of chains.
+    ///
+    /// # Arguments
+    ///
+    /// `query` - The filter to apply, see [`ChainQuery`].
+    pub fn query<'a>(&'a self, query: &'a ChainQuery) -> impl Iterator<Item = &'a ChainInfo> {
+        self.chains()
+            .filter(move |chain_info| query.matches(chain_info))
+    }
+
+    /// Iterates over the chain_ids of every Cosmos chain in the registry.
+    pub fn chain_ids(&self) -> impl Iterator<Item = &str> {
+        self.chains().map(|chain_info| chain_info.chain_id.as_str())
+    }
+
+    /// Iterates over the chain_names of every Cosmos chain in the registry.
+    pub fn chain_names(&self) -> impl Iterator<Item = &str> {
+        self.chains()
+            .map(|chain_info| chain_info.chain_name.as_str())
     }
 
     /// Get a chain's information from the registry based on the chain_id.
-    /// Returns `None` if the chain_id is not found.
+    /// Returns [`Error::NotFound`] if the chain_id is not found, [`Error::Parse`] if the
+    /// `chain.json` declaring it is malformed and [`Error::DuplicateChainId`] if more than one
+    /// chain declares it.
     ///
     /// # Arguments
     ///
     /// `chain_id` - The chain_id of the chain to get information for. This is the `chain_id` field in the chain's `chain.json` file. For example, the `chain_id` for the Cosmos Hub is `cosmoshub-4`.
     pub fn get_by_chain_id(&self, chain_id: &str) -> Result<ChainInfo, Error> {
-        for file in glob::glob(&self.path.join("**/chain.json").to_string_lossy())? {
-            let file = file?;
-            let chain_res: Result<ChainInfo, serde_json::Error> =
-                serde_json::from_reader(std::fs::File::open(file)?);
-
-            if let Ok(chain_info) = chain_res {
-                if chain_info.chain_id == chain_id {
-                    return Ok(chain_info);
-                }
-            }
-        }
+        self.index.by_chain_id(chain_id).cloned()
+    }
+
+    /// Get a chain's information from the registry based on the chain_name.
+    /// Returns [`Error::NotFound`] if the chain_name is not found, [`Error::DuplicateChainName`]
+    /// if more than one chain declares it.
+    ///
+    /// # Arguments
+    ///
+    /// `chain_name` - The `chain_name` field in the chain's `chain.json` file, which is also the
+    /// key used by the registry's IBC and asset files. For example, `cosmoshub`.
+    pub fn get_by_chain_name(&self, chain_name: &str) -> Result<ChainInfo, Error> {
+        self.index.by_chain_name(chain_name).cloned()
+    }
+
+    /// Get a chain's information from the registry based on its directory in the registry.
+    /// Returns [`Error::NotFound`] if the directory does not contain a `chain.json`.
+    ///
+    /// # Arguments
+    ///
+    /// `directory` - The chain's directory relative to the registry root, using `/` as the
+    /// separator. For example, `juno` or `testnets/junotestnet`.
+    pub fn get_by_directory(&self, directory: &str) -> Result<ChainInfo, Error> {
+        self.index.by_directory(directory).cloned()
+    }
+
+    /// Get a chain's assets from the `assetlist.json` in its directory.
+    /// Returns [`Error::NotFound`] if the chain has no asset list, and
+    /// [`Error::DuplicateAssetList`] if more than one directory has an asset list for it.
+    ///
+    /// # Arguments
+    ///
+    /// `chain_name` - The chain_name of the chain, e.g. `osmosis`.
+    pub fn get_assets(&self, chain_name: &str) -> Result<AssetList, Error> {
+        self.index.assets(chain_name).cloned()
+    }
+
+    /// Get an asset of a chain by its base denom.
+    /// Returns [`Error::NotFound`] if the chain does not list the asset.
+    ///
+    /// # Arguments
+    ///
+    /// `chain_name` - The chain_name of the chain the asset is on, e.g. `osmosis`.
+    ///
+    /// `base_denom` - The base denom of the asset on that chain, e.g. `uosmo` or `ibc/27394FB...`.
+    pub fn get_asset_by_base(&self, chain_name: &str, base_denom: &str) -> Result<Asset, Error> {
+        self.index
+            .assets(chain_name)?
+            .get_by_base(base_denom)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("asset {} on {}", base_denom, chain_name)))
+    }
+
+    /// Get an asset of a chain by its symbol, ignoring case.
+    /// Returns [`Error::NotFound`] if the chain does not list the asset.
+    ///
+    /// # Arguments
+    ///
+    /// `chain_name` - The chain_name of the chain the asset is on, e.g. `osmosis`.
+    ///
+    /// `symbol` - The symbol of the asset, e.g. `ATOM`.
+    pub fn get_asset_by_symbol(&self, chain_name: &str, symbol: &str) -> Result<Asset, Error> {
+        self.index
+            .assets(chain_name)?
+            .get_by_symbol(symbol)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("asset {} on {}", symbol, chain_name)))
+    }
+
+    /// Get the IBC connection between two chains from the registry's `_IBC` directory.
+    /// Returns [`Error::NotFound`] if the registry has no IBC data for the pair.
+    ///
+    /// # Arguments
+    ///
+    /// `chain_a`, `chain_b` - The chain_names of the two chains, in any order.
+    pub fn get_ibc_data(&self, chain_a: &str, chain_b: &str) -> Result<IbcData, Error> {
+        self.index.ibc(chain_a, chain_b).cloned()
+    }
+
+    /// Get the IBC channels between two chains, as seen from `chain_a`: the channel, port,
+    /// client and connection ids on `chain_a` along with the counterparty ids on `chain_b`.
+    /// Returns [`Error::NotFound`] if the registry has no IBC data for the pair.
+    ///
+    /// # Arguments
+    ///
+    /// `chain_a` - The chain_name of the chain the channels are seen from, e.g. `juno`.
+    ///
+    /// `chain_b` - The chain_name of the counterparty chain, e.g. `osmosis`.
+    pub fn ibc_channels(&self, chain_a: &str, chain_b: &str) -> Result<Vec<ChannelInfo>, Error> {
+        let ibc_data = self.index.ibc(chain_a, chain_b)?;
+        Ok(ibc_data.channels_from(chain_a).unwrap_or_default())
+    }
+
+    /// Resolve an `ibc/<hash>` denom held on a chain to the chain and asset it originates from.
+    /// The asset's traces in the chain's `assetlist.json` are followed when it is listed there,
+    /// otherwise the denoms of the assets of every chain connected over a transfer channel are
+    /// computed and compared. Returns [`Error::NotFound`] if the denom cannot be resolved.
+    ///
+    /// # Arguments
+    ///
+    /// `chain_name` - The chain_name of the chain holding the denom, e.g. `osmosis`.
+    ///
+    /// `denom` - The IBC denom, e.g. `ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2`.
+    pub fn resolve_ibc_denom(&self, chain_name: &str, denom: &str) -> Result<ResolvedDenom, Error> {
+        denom::resolve(&self.index, chain_name, denom)
+    }
+
+    /// Find the IBC route with the fewest hops between two chains over the registry's transfer
+    /// channels. Preferred channels are used when several routes have the same length.
+    /// Returns [`Error::NotFound`] if the chains are not connected within the options' limits.
+    ///
+    /// # Arguments
+    ///
+    /// `from` - The chain_name of the chain the transfer starts on, e.g. `juno`.
+    ///
+    /// `to` - The chain_name of the destination chain, e.g. `cosmoshub`.
+    ///
+    /// `options` - Which chains and channels the route may use, see [`RouteOptions`].
+    pub fn find_ibc_route(
+        &self,
+        from: &str,
+        to: &str,
+        options: &RouteOptions,
+    ) -> Result<IbcRoute, Error> {
+        ChannelGraph::new(self.index.ibc_data(), options)
+            .shortest(from, to, options.max_hops)
+            .ok_or_else(|| Error::NotFound(format!("IBC route from {} to {}", from, to)))
+    }
 
-        Err("Chain not found".into())
+    /// Find the IBC routes between two chains over the registry's transfer channels that visit
+    /// no chain twice, shortest first, up to the options' `max_routes`.
+    ///
+    /// # Arguments
+    ///
+    /// `from` - The chain_name of the chain the transfer starts on, e.g. `juno`.
+    ///
+    /// `to` - The chain_name of the destination chain, e.g. `cosmoshub`.
+    ///
+    /// `options` - Which chains and channels the routes may use, see [`RouteOptions`].
+    pub fn find_ibc_routes(&self, from: &str, to: &str, options: &RouteOptions) -> Vec<IbcRoute> {
+        ChannelGraph::new(self.index.ibc_data(), options).all(
+            from,
+            to,
+            options.max_hops,
+            options.max_routes,
+        )
+    }
+
+    /// Start building a multi-hop transfer from `source` to `destination` over the shortest
+    /// IBC route, forwarded by the packet forward middleware of the intermediate chains. See
+    /// [`ForwardTransferBuilder`].
+    ///
+    /// # Arguments
+    ///
+    /// `source` - The chain_name of the chain the transfer is sent from, e.g. `juno`.
+    ///
+    /// `destination` - The chain_name of the destination chain, e.g. `cosmoshub`.
+    ///
+    /// `receiver` - The bech32 address of the receiver on the destination chain.
+    pub fn forward_transfer(
+        &self,
+        source: &str,
+        destination: &str,
+        receiver: &str,
+    ) -> ForwardTransferBuilder<'_> {
+        ForwardTransferBuilder::new(self, source, destination, receiver)
+    }
+
+    /// Get all chains in the registry using the given bech32 prefix. Mainnets and their testnets
+    /// usually share a prefix, so this can return more than one chain.
+    ///
+    /// # Arguments
+    ///
+    /// `prefix` - The `bech32_prefix` field in the chain's `chain.json` file, e.g. `juno`.
+    pub fn get_by_bech32_prefix(&self, prefix: &str) -> Vec<ChainInfo> {
+        self.index.by_bech32_prefix(prefix).cloned().collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use serial_test::serial;
-
     use super::*;
 
+    const FIXTURE_PATH: &str = "tests/fixtures/chain-registry";
+
     #[test]
-    #[serial]
     fn can_get_chain_registry_data() {
         let registry = ChainRegistry::from_remote();
         assert!(registry.is_ok());
     }
 
     #[test]
-    #[serial]
+    fn can_get_chain_config_by_id_from_path() {
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+        let info = registry.get_by_chain_id("juno-1").unwrap();
+
+        assert_eq!(info.chain_name, "juno");
+        assert_eq!(info.chain_id, "juno-1");
+        assert_eq!(info.pretty_name, "Juno");
+
+        let info = registry.get_by_chain_id("uni-6").unwrap();
+
+        assert_eq!(info.chain_name, "junotestnet");
+        assert_eq!(info.chain_id, "uni-6");
+
+        assert!(matches!(
+            registry.get_by_chain_id("not-a-chain-1"),
+            Err(Error::NotFound(_))
+        ));
+        assert_eq!(
+            registry
+                .get_by_chain_id("not-a-chain-1")
+                .unwrap_err()
+                .to_string(),
+            "not found: chain not-a-chain-1"
+        );
+    }
+
+    #[test]
+    fn can_get_chain_config_by_name_and_directory() {
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+
+        let info = registry.get_by_chain_name("junotestnet").unwrap();
+        assert_eq!(info.chain_id, "uni-6");
+        assert_eq!(
+            info,
+            registry.get_by_directory("testnets/junotestnet").unwrap()
+        );
+
+        let info = registry.get_by_directory("osmosis/").unwrap();
+        assert_eq!(info.chain_name, "osmosis");
+
+        assert!(matches!(
+            registry.get_by_chain_name("not-a-chain"),
+            Err(Error::NotFound(_))
+        ));
+        assert!(matches!(
+            registry.get_by_directory("testnets"),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn can_enumerate_chains() {
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+
+        let names: Vec<_> = registry.chain_names().collect();
+        assert_eq!(
+            names,
+            ["cosmoshub", "juno", "osmosis", "stargaze", "junotestnet"]
+        );
+        assert!(registry.chain_ids().all(|id| id != "templatechain-1"));
+        assert!(registry.get_by_chain_id("templatechain-1").is_err());
+
+        let mainnets: Vec<_> = registry
+            .chains_with(ChainListOptions::default().testnets(false))
+            .map(|info| info.chain_id.as_str())
+            .collect();
+        assert_eq!(
+            mainnets,
+            ["cosmoshub-4", "juno-1", "osmosis-1", "stargaze-1"]
+        );
+
+        let all: Vec<_> = registry
+            .chains_with(ChainListOptions::all())
+            .map(|info| info.chain_name.as_str())
+            .collect();
+        assert_eq!(
+            all,
+            [
+                "ethereum",
+                "cosmoshub",
+                "juno",
+                "osmosis",
+                "stargaze",
+                "junotestnet"
+            ]
+        );
+    }
+
+    #[test]
+    fn can_query_chains() {
+        use query::Comparison;
+
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+        let query = ChainQuery::network_type("mainnet")
+            .and(ChainQuery::cosmwasm_enabled(true))
+            .and(ChainQuery::cosmos_sdk_version(Comparison::Ge, "0.45"))
+            .and(!ChainQuery::fee_denom("uosmo"));
+
+        let names: Vec<_> = registry
+            .query(&query)
+            .map(|info| info.chain_name.as_str())
+            .collect();
+        assert_eq!(names, ["juno"]);
+    }
+
+    #[test]
+    fn can_get_assets() {
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+
+        let assets = registry.get_assets("juno").unwrap();
+        assert_eq!(assets.chain_name, "juno");
+        assert_eq!(assets.assets.len(), 3);
+
+        // The fee token of a chain resolves to a displayable asset
+        let info = registry.get_by_chain_name("juno").unwrap();
+        let asset = registry
+            .get_asset_by_base("juno", &info.fees.fee_tokens[0].denom)
+            .unwrap();
+        assert_eq!(asset.symbol, "JUNO");
+        assert_eq!(asset.to_display_amount(1_250_000), "1.25");
+
+        let atom = registry.get_asset_by_symbol("osmosis", "atom").unwrap();
+        assert_eq!(
+            atom.base,
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+        );
+        assert_eq!(atom.traces[0].counterparty.chain_name, "cosmoshub");
+
+        // Asset lists of non-cosmos chains are loaded too
+        assert_eq!(
+            registry
+                .get_asset_by_base("ethereum", "wei")
+                .unwrap()
+                .decimals(),
+            18
+        );
+
+        assert!(matches!(
+            registry.get_assets("junotestnet"),
+            Err(Error::NotFound(_))
+        ));
+        assert!(matches!(
+            registry.get_asset_by_base("juno", "uatom"),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn can_get_ibc_channels() {
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+
+        let channels = registry.ibc_channels("osmosis", "juno").unwrap();
+        assert_eq!(channels.len(), 2);
+
+        let transfer = &channels[0];
+        assert!(transfer.is_transfer() && transfer.is_live() && transfer.preferred);
+        assert_eq!(transfer.chain_name, "osmosis");
+        assert_eq!(transfer.channel_id, "channel-42");
+        assert_eq!(transfer.connection_id, "connection-1142");
+        assert_eq!(transfer.counterparty_chain_name, "juno");
+        assert_eq!(transfer.counterparty_channel_id, "channel-0");
+        assert_eq!(transfer.ordering, "unordered");
+        assert_eq!(transfer.version, "ics20-1");
+
+        let wasm = &channels[1];
+        assert!(!wasm.is_transfer() && !wasm.is_live());
+        assert!(wasm.is_killed());
+        assert_eq!(wasm.status, Some(chain::ChainStatus::Killed));
+        assert!(wasm.counterparty_port_id.starts_with("wasm."));
+
+        // The same channel seen from the other side
+        let channels = registry.ibc_channels("juno", "osmosis").unwrap();
+        assert_eq!(channels[0].channel_id, "channel-0");
+        assert_eq!(channels[0].counterparty_channel_id, "channel-42");
+
+        assert!(matches!(
+            registry.ibc_channels("juno", "junotestnet"),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn can_find_ibc_routes() {
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+
+        let route = registry
+            .find_ibc_route("juno", "cosmoshub", &RouteOptions::default())
+            .unwrap();
+        assert_eq!(route.chains(), ["juno", "cosmoshub"]);
+        assert_eq!(route.hops[0].channel_id, "channel-1");
+
+        let routes = registry.find_ibc_routes("juno", "cosmoshub", &RouteOptions::default());
+        let paths: Vec<_> = routes.iter().map(IbcRoute::chains).collect();
+        assert_eq!(
+            paths,
+            [
+                vec!["juno", "cosmoshub"],
+                vec!["juno", "osmosis", "cosmoshub"]
+            ]
+        );
+
+        let route = registry
+            .find_ibc_route(
+                "juno",
+                "cosmoshub",
+                &RouteOptions::default().require_live(true),
+            )
+            .unwrap();
+        assert_eq!(route.len(), 1);
+
+        // The wasm channel between juno and osmosis is not a transfer channel
+        let options = RouteOptions::default().exclude("cosmoshub");
+        let routes = registry.find_ibc_routes("juno", "osmosis", &options);
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].hops[0].counterparty_channel_id, "channel-42");
+
+        assert!(matches!(
+            registry.find_ibc_route(
+                "juno",
+                "cosmoshub",
+                &RouteOptions::default().exclude("cosmoshub")
+            ),
+            Err(Error::NotFound(_))
+        ));
+        let options = RouteOptions::default().max_hops(1);
+        assert_eq!(
+            registry
+                .find_ibc_routes("juno", "cosmoshub", &options)
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn can_resolve_ibc_denoms() {
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+
+        // Listed in the chain's asset list with a trace
+        let resolved = registry
+            .resolve_ibc_denom(
+                "juno",
+                "ibc/C4CFF46FD6DE35CA4CF4CE031E643C8FDC9BA4B99AE598E9B0ED98FE3A2319F9",
+            )
+            .unwrap();
+        assert_eq!(resolved.chain_name, "cosmoshub");
+        assert_eq!(resolved.asset.symbol, "ATOM");
+        assert_eq!(resolved.trace.path(), "transfer/channel-1/uatom");
+
+        // Not listed, resolved from the IBC channels, in lower case
+        let resolved = registry
+            .resolve_ibc_denom(
+                "osmosis",
+                "ibc/46b44899322f3cd854d2d46deef881958467cdd4b3b10086da49296bbed94bed",
+            )
+            .unwrap();
+        assert_eq!(resolved.chain_name, "juno");
+        assert_eq!(resolved.asset.base, "ujuno");
+        assert_eq!(resolved.trace.path(), "transfer/channel-42/ujuno");
+
+        // Not listed and received by the counterparty over IBC itself: juno's ATOM voucher,
+        // forwarded to osmosis
+        let two_hops = denom::DenomTrace::parse("transfer/channel-42/transfer/channel-1/uatom");
+        let resolved = registry
+            .resolve_ibc_denom("osmosis", &two_hops.ibc_denom())
+            .unwrap();
+        assert_eq!(resolved.chain_name, "cosmoshub");
+        assert_eq!(resolved.asset.base, "uatom");
+        assert_eq!(resolved.trace, two_hops);
+
+        assert!(matches!(
+            registry.resolve_ibc_denom("osmosis", "ibc/0000"),
+            Err(Error::NotFound(_))
+        ));
+        assert!(registry.resolve_ibc_denom("osmosis", "uosmo").is_err());
+    }
+
+    #[test]
+    fn can_build_forward_transfers() {
+        use bech32::ToBase32;
+
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+        let receiver =
+            bech32::encode("stars", [7u8; 20].to_base32(), bech32::Variant::Bech32).unwrap();
+        let osmo_receiver = pfm::convert_address(&receiver, "osmo").unwrap();
+
+        // Stargaze is only connected to osmosis
+        let transfer = registry
+            .forward_transfer("juno", "stargaze", &receiver)
+            .timeout("30m")
+            .build()
+            .unwrap();
+        assert_eq!(transfer.route.chains(), ["juno", "osmosis", "stargaze"]);
+        assert_eq!(transfer.msg_transfer.source_port, "transfer");
+        assert_eq!(transfer.msg_transfer.source_channel, "channel-0");
+        assert_eq!(transfer.msg_transfer.receiver, osmo_receiver);
+        assert_eq!(
+            transfer.msg_transfer.memo,
+            format!(
+                r#"{{"forward":{{"receiver":"{}","port":"transfer","channel":"channel-75","timeout":"30m","retries":2}}}}"#,
+                receiver
+            )
+        );
+
+        // A direct transfer needs no memo
+        let transfer = registry
+            .forward_transfer("osmosis", "stargaze", &receiver)
+            .build()
+            .unwrap();
+        assert_eq!(transfer.msg_transfer.source_channel, "channel-75");
+        assert_eq!(transfer.msg_transfer.receiver, receiver);
+        assert!(transfer.memo.is_none() && transfer.msg_transfer.memo.is_empty());
+
+        // The receiver must be an address of the destination chain, direct transfers included
+        for receiver in [osmo_receiver.as_str(), "not-an-address"] {
+            assert!(matches!(
+                registry
+                    .forward_transfer("osmosis", "stargaze", receiver)
+                    .build(),
+                Err(Error::InvalidAddress { .. })
+            ));
+        }
+
+        assert!(matches!(
+            registry
+                .forward_transfer("juno", "stargaze", "not-an-address")
+                .build(),
+            Err(Error::InvalidAddress { .. })
+        ));
+        assert!(registry
+            .forward_transfer("juno", "stargaze", &receiver)
+            .route_options(RouteOptions::default().exclude("osmosis"))
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn can_export_hermes_config() {
+        use export::hermes::{self, HermesConfig};
+
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+        let config = hermes::config(&registry, &["juno", "osmosis", "cosmoshub"]).unwrap();
+        assert_eq!(config.chains.len(), 3);
+
+        let juno = &config.chains[0];
+        assert_eq!(juno.id, "juno-1");
+        assert_eq!(juno.rpc_addr, "https://rpc-juno.itastakers.com");
+        assert_eq!(juno.grpc_addr, "http://juno-grpc.polkachu.com:12690");
+        assert_eq!(
+            juno.event_source.url,
+            "wss://rpc-juno.itastakers.com/websocket"
+        );
+        assert_eq!(juno.account_prefix, "juno");
+        assert_eq!(juno.gas_price.price, 0.0625);
+        assert_eq!(juno.gas_price.denom, "ujuno");
+        assert_eq!(juno.address_type.derivation, "cosmos");
+        // The killed wasm channel to osmosis is left out
+        assert_eq!(
+            juno.packet_filter.list,
+            [
+                ("transfer".to_string(), "channel-0".to_string()),
+                ("transfer".to_string(), "channel-1".to_string())
+            ]
+        );
+
+        let osmosis = &config.chains[1];
+        assert_eq!(osmosis.rpc_addr, "https://rpc.osmosis.zone");
+        assert_eq!(
+            osmosis.packet_filter.list,
+            [
+                ("transfer".to_string(), "channel-42".to_string()),
+                ("transfer".to_string(), "channel-0".to_string())
+            ]
+        );
+        assert_eq!(
+            config.chains[2].grpc_addr,
+            "https://grpc-cosmoshub-ia.cosmosia.notional.ventures:443"
+        );
+
+        let toml = config.to_toml();
+        assert!(toml.contains("[[chains]]"));
+        assert_eq!(toml::from_str::<HermesConfig>(&toml).unwrap(), config);
+
+        assert!(matches!(
+            hermes::config(&registry, &["juno", "nonexistent"]),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn can_export_rly_config() {
+        use export::rly::{self, RlyChain, RlyPath};
+
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+        let config = rly::config(&registry, &["stargaze", "juno", "osmosis"]).unwrap();
+        assert_eq!(
+            config.chains.keys().collect::<Vec<_>>(),
+            ["juno", "osmosis", "stargaze"]
+        );
+
+        let juno = &config.chains["juno"].value;
+        assert_eq!(juno.chain_id, "juno-1");
+        assert_eq!(juno.rpc_addr, "https://rpc-juno.itastakers.com");
+        assert_eq!(juno.account_prefix, "juno");
+        assert_eq!(juno.gas_prices, "0.0625ujuno");
+        assert_eq!(juno.coin_type, 118);
+        assert_eq!(config.chains["stargaze"].value.gas_prices, "1.1ustars");
+
+        // Stargaze is only connected to osmosis
+        assert_eq!(
+            config.paths.keys().collect::<Vec<_>>(),
+            ["juno-osmosis", "osmosis-stargaze"]
+        );
+        let path = &config.paths["juno-osmosis"];
+        assert_eq!(path.src.chain_id, "juno-1");
+        assert_eq!(path.src.client_id, "07-tendermint-3");
+        assert_eq!(path.dst.chain_id, "osmosis-1");
+        assert_eq!(path.dst.connection_id, "connection-1142");
+        assert_eq!(path.src_channel_filter.channel_list, ["channel-0"]);
+
+        let dir = tempfile::tempdir().unwrap();
+        config.write(dir.path()).unwrap();
+        let written: RlyChain = serde_json::from_str(
+            &std::fs::read_to_string(dir.path().join("chains/juno.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(written, config.chains["juno"]);
+        let written = std::fs::read_to_string(dir.path().join("paths/juno-osmosis.json")).unwrap();
+        assert!(written.contains("\"src-channel-filter\""));
+        assert_eq!(serde_json::from_str::<RlyPath>(&written).unwrap(), *path);
+    }
+
+    #[test]
+    fn can_export_keplr_chain_info() {
+        use export::keplr;
+
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+        let chain_info = keplr::suggest_chain(&registry, "juno").unwrap();
+
+        assert_eq!(chain_info.chain_id, "juno-1");
+        assert_eq!(chain_info.chain_name, "Juno");
+        assert_eq!(chain_info.rest, "https://lcd-juno.itastakers.com");
+        assert_eq!(chain_info.bip44.coin_type, 118);
+        assert_eq!(
+            chain_info.bech32_config.bech32_prefix_val_addr,
+            "junovaloper"
+        );
+        assert_eq!(chain_info.currencies.len(), 3);
+        assert_eq!(chain_info.stake_currency.coin_denom, "JUNO");
+        assert_eq!(chain_info.stake_currency.coin_decimals, 6);
+        assert_eq!(
+            chain_info.stake_currency.coin_gecko_id.as_deref(),
+            Some("juno-network")
+        );
+        assert_eq!(chain_info.fee_currencies[0].gas_price_step.low, 0.03);
+        assert_eq!(chain_info.features, ["cosmwasm"]);
+
+        let json = serde_json::to_value(&chain_info).unwrap();
+        assert_eq!(json["bech32Config"]["bech32PrefixAccPub"], "junopub");
+        assert_eq!(json["feeCurrencies"][0]["coinMinimalDenom"], "ujuno");
+        assert_eq!(json["feeCurrencies"][0]["gasPriceStep"]["average"], 0.0625);
+
+        // Stargaze has no asset list
+        assert!(matches!(
+            keplr::suggest_chain(&registry, "stargaze"),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn can_export_node_config() {
+        use export::node;
+
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+        let config = node::config(&registry, "juno").unwrap();
+
+        assert_eq!(
+            config.seeds,
+            "2484353dab0b2c1275765b8ffa2c50b3b36158ca@seed-node.junochain.com:26656,\
+             ef2315d81caa27e4b0fd0f267d301569ee958893@juno-seed.panthea.eu:26656"
+        );
+        assert_eq!(
+            config.persistent_peers,
+            "b1f46f1a1955fc773d3b73180179b0e0a07adce1@162.55.244.250:39656"
+        );
+        assert_eq!(config.minimum_gas_prices, "0.0025ujuno");
+        // A zero fixed minimum falls back to the low gas price
+        let cosmoshub = node::config(&registry, "cosmoshub").unwrap();
+        assert_eq!(cosmoshub.minimum_gas_prices, "0.01uatom");
+        assert!(config.home_dir().ends_with(".juno"));
+        assert!(!config.home_dir().starts_with("$HOME"));
+        assert!(config
+            .app_toml()
+            .starts_with("minimum-gas-prices = \"0.0025ujuno\""));
+
+        // Keys are set in the files written by `junod init`, keeping the rest
+        let home = tempfile::tempdir().unwrap();
+        let config_dir = home.path().join("config");
+        std::fs::create_dir(&config_dir).unwrap();
+        std::fs::write(
+            config_dir.join("config.toml"),
+            "moniker = \"node\"\n\n[p2p]\n# Comma separated list of seed nodes\nseeds = \"\"\nladdr = \"tcp://0.0.0.0:26656\"\n\n[mempool]\nsize = 5000\n",
+        )
+        .unwrap();
+        config.write(home.path()).unwrap();
+
+        let config_toml = std::fs::read_to_string(config_dir.join("config.toml")).unwrap();
+        assert!(config_toml.contains("# Comma separated list of seed nodes"));
+        let parsed: toml::Value = toml::from_str(&config_toml).unwrap();
+        assert_eq!(parsed["moniker"].as_str(), Some("node"));
+        assert_eq!(parsed["p2p"]["seeds"].as_str(), Some(config.seeds.as_str()));
+        assert_eq!(
+            parsed["p2p"]["persistent_peers"].as_str(),
+            Some(config.persistent_peers.as_str())
+        );
+        assert_eq!(parsed["mempool"]["size"].as_integer(), Some(5000));
+
+        let app_toml: toml::Value =
+            toml::from_str(&std::fs::read_to_string(config_dir.join("app.toml")).unwrap()).unwrap();
+        assert_eq!(app_toml["minimum-gas-prices"].as_str(), Some("0.0025ujuno"));
+
+        let client_toml: toml::Value =
+            toml::from_str(&std::fs::read_to_string(config_dir.join("client.toml")).unwrap())
+                .unwrap();
+        assert_eq!(client_toml["chain-id"].as_str(), Some("juno-1"));
+        assert_eq!(client_toml["node"].as_str(), Some(node::LOCAL_NODE_RPC));
+    }
+
+    #[test]
+    fn can_parse_status_network_type_and_key_algos() {
+        use chain::{ChainStatus, KeyAlgo, NetworkType};
+
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+        let juno = registry.get_by_chain_name("juno").unwrap();
+        assert_eq!(juno.status, ChainStatus::Live);
+        assert_eq!(juno.network_type, NetworkType::Mainnet);
+        assert_eq!(juno.key_algos, [KeyAlgo::Secp256k1]);
+
+        // Values this crate does not know of are kept as-is
+        let json = r#"{"status":"halted","network_type":"mainet","key_algos":["ethsecp256k1","bls12381"]}"#;
+        let info: ChainInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.status, ChainStatus::Unknown("halted".to_string()));
+        assert_eq!(info.network_type.as_str(), "mainet");
+        assert_eq!(
+            info.key_algos,
+            [
+                KeyAlgo::EthSecp256k1,
+                KeyAlgo::Unknown("bls12381".to_string())
+            ]
+        );
+        let written = serde_json::to_value(&info).unwrap();
+        assert_eq!(written["status"], "halted");
+        assert_eq!(written["key_algos"][0], "ethsecp256k1");
+        assert_eq!(ChainInfo::default().status.as_str(), "");
+    }
+
+    #[test]
+    fn can_round_trip_gas_prices() {
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+
+        // Prices are written back exactly as the registry has them
+        for chain_name in ["cosmoshub", "juno", "osmosis", "stargaze"] {
+            let path = Path::new(FIXTURE_PATH).join(chain_name).join("chain.json");
+            let original: String = std::fs::read_to_string(path)
+                .unwrap()
+                .split_whitespace()
+                .collect();
+            let info = registry.get_by_chain_name(chain_name).unwrap();
+            let fees = serde_json::to_string(&info.fees).unwrap();
+            assert!(original.contains(&format!("\"fees\":{}", fees)), "{}", fees);
+        }
+
+        let juno = registry.get_by_chain_name("juno").unwrap();
+        let fee = juno
+            .estimate_fee(200_000, fee::GasPriceTier::Low, None)
+            .unwrap();
+        assert_eq!(fee.to_string(), "6000ujuno");
+    }
+
+    #[test]
+    fn reports_malformed_and_duplicate_chains() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["juno", "juno-copy", "broken"] {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+        }
+        let juno = Path::new(FIXTURE_PATH).join("juno/chain.json");
+        std::fs::copy(&juno, dir.path().join("juno/chain.json")).unwrap();
+        std::fs::copy(&juno, dir.path().join("juno-copy/chain.json")).unwrap();
+        let assets = Path::new(FIXTURE_PATH).join("juno/assetlist.json");
+        std::fs::copy(&assets, dir.path().join("juno/assetlist.json")).unwrap();
+        std::fs::copy(&assets, dir.path().join("juno-copy/assetlist.json")).unwrap();
+        std::fs::write(
+            dir.path().join("broken/chain.json"),
+            r#"{ "chain_id": "broken-1", "slip44": "not a number" }"#,
+        )
+        .unwrap();
+
+        let registry = ChainRegistry::from_path(dir.path()).unwrap();
+
+        match registry.get_by_chain_id("broken-1") {
+            Err(Error::Parse { path, source }) => {
+                assert!(path.ends_with("broken/chain.json"));
+                assert_eq!(source.line(), 1);
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        assert!(matches!(
+            registry.get_by_directory("broken"),
+            Err(Error::Parse { .. })
+        ));
+        match registry.get_by_chain_id("juno-1") {
+            Err(Error::DuplicateChainId { chain_id, paths }) => {
+                assert_eq!(chain_id, "juno-1");
+                assert_eq!(paths.len(), 2);
+            }
+            other => panic!("expected a duplicate chain error, got {:?}", other),
+        }
+        match registry.get_by_chain_name("juno") {
+            Err(Error::DuplicateChainName { chain_name, paths }) => {
+                assert_eq!(chain_name, "juno");
+                assert_eq!(paths.len(), 2);
+            }
+            other => panic!("expected a duplicate chain error, got {:?}", other),
+        }
+        match registry.get_assets("juno") {
+            Err(Error::DuplicateAssetList { chain_name, paths }) => {
+                assert_eq!(chain_name, "juno");
+                assert_eq!(paths.len(), 2);
+            }
+            other => panic!("expected a duplicate asset list error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn error_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<Error>();
+    }
+
+    #[test]
+    fn can_get_chains_by_bech32_prefix() {
+        let registry = ChainRegistry::from_path(FIXTURE_PATH).unwrap();
+        let mut names: Vec<_> = registry
+            .get_by_bech32_prefix("juno")
+            .into_iter()
+            .map(|info| info.chain_name)
+            .collect();
+        names.sort();
+
+        assert_eq!(names, ["juno", "junotestnet"]);
+        assert!(registry.get_by_bech32_prefix("nope").is_empty());
+    }
+
+    #[test]
+    fn reload_picks_up_new_chains() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("juno")).unwrap();
+        std::fs::copy(
+            Path::new(FIXTURE_PATH).join("juno/chain.json"),
+            dir.path().join("juno/chain.json"),
+        )
+        .unwrap();
+
+        let mut registry = ChainRegistry::from_path(dir.path()).unwrap();
+        assert!(registry.get_by_chain_id("osmosis-1").is_err());
+
+        std::fs::create_dir(dir.path().join("osmosis")).unwrap();
+        std::fs::copy(
+            Path::new(FIXTURE_PATH).join("osmosis/chain.json"),
+            dir.path().join("osmosis/chain.json"),
+        )
+        .unwrap();
+        registry.reload().unwrap();
+
+        assert_eq!(
+            registry.get_by_chain_id("osmosis-1").unwrap().chain_name,
+            "osmosis"
+        );
+    }
+
+    #[test]
+    fn from_path_rejects_non_registry_directories() {
+        assert!(matches!(
+            ChainRegistry::from_path("tests/fixtures/does-not-exist"),
+            Err(Error::InvalidRegistry { .. })
+        ));
+        assert!(matches!(
+            ChainRegistry::from_path("src"),
+            Err(Error::InvalidRegistry { .. })
+        ));
+    }
+
+    #[test]
     fn can_get_chain_config_by_id() {
         let registry = ChainRegistry::from_remote().unwrap();
         let info = registry.get_by_chain_id("juno-1").unwrap();
@@ -0,0 +1,116 @@
+//! Contains the options used to select which chains are returned when enumerating the registry.
+use crate::chain::NetworkType;
+use crate::ChainInfo;
+
+/// The registry directory holding chains that are not Cosmos SDK based.
+pub const NON_COSMOS_DIR: &str = "_non-cosmos";
+
+/// The registry directory holding testnet chains.
+pub const TESTNETS_DIR: &str = "testnets";
+
+/// The registry directory holding devnet chains.
+pub const DEVNETS_DIR: &str = "devnets";
+
+/// The kind of a chain, derived from where it is located in the registry and its `network_type`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChainKind {
+    Mainnet,
+    Testnet,
+    Devnet,
+    /// A chain under the registry's `_non-cosmos` directory.
+    NonCosmos,
+}
+
+impl ChainKind {
+    /// Classifies a chain from its directory relative to the registry root and its `chain.json`.
+    pub(crate) fn classify(directory: &str, chain_info: &ChainInfo) -> Self {
+        let section = directory.split('/').next().unwrap_or_default();
+
+        if section == NON_COSMOS_DIR {
+            ChainKind::NonCosmos
+        } else if section == DEVNETS_DIR || chain_info.network_type == NetworkType::Devnet {
+            ChainKind::Devnet
+        } else if section == TESTNETS_DIR || chain_info.network_type == NetworkType::Testnet {
+            ChainKind::Testnet
+        } else {
+            ChainKind::Mainnet
+        }
+    }
+}
+
+/// Selects which kinds of chains are returned by
+/// [`ChainRegistry::chains_with`](crate::ChainRegistry::chains_with).
+///
+/// By default every Cosmos chain is included and `_non-cosmos` entries are excluded. The
+/// registry's `_template` and other underscore prefixed helper directories are never included.
+///
+/// ## Example
+///
+/// ```rust
+/// use cosmos_chain_registry::list::ChainListOptions;
+///
+/// // Only mainnets
+/// let options = ChainListOptions::default().testnets(false).devnets(false);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainListOptions {
+    pub include_mainnets: bool,
+    pub include_testnets: bool,
+    pub include_devnets: bool,
+    pub include_non_cosmos: bool,
+}
+
+impl Default for ChainListOptions {
+    fn default() -> Self {
+        Self {
+            include_mainnets: true,
+            include_testnets: true,
+            include_devnets: true,
+            include_non_cosmos: false,
+        }
+    }
+}
+
+impl ChainListOptions {
+    /// Options including every chain in the registry, `_non-cosmos` entries included.
+    pub fn all() -> Self {
+        Self {
+            include_non_cosmos: true,
+            ..Self::default()
+        }
+    }
+
+    /// Sets whether mainnets are included.
+    pub fn mainnets(mut self, include: bool) -> Self {
+        self.include_mainnets = include;
+        self
+    }
+
+    /// Sets whether testnets are included.
+    pub fn testnets(mut self, include: bool) -> Self {
+        self.include_testnets = include;
+        self
+    }
+
+    /// Sets whether devnets are included.
+    pub fn devnets(mut self, include: bool) -> Self {
+        self.include_devnets = include;
+        self
+    }
+
+    /// Sets whether `_non-cosmos` entries are included.
+    pub fn non_cosmos(mut self, include: bool) -> Self {
+        self.include_non_cosmos = include;
+        self
+    }
+
+    /// Returns `true` if chains of the given kind are included.
+    pub fn includes(&self, kind: ChainKind) -> bool {
+        match kind {
+            ChainKind::Mainnet => self.include_mainnets,
+            ChainKind::Testnet => self.include_testnets,
+            ChainKind::Devnet => self.include_devnets,
+            ChainKind::NonCosmos => self.include_non_cosmos,
+        }
+    }
+}
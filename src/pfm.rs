@@ -0,0 +1,229 @@
+//! Contains the builder of multi-hop ICS-20 transfers using the
+//! [packet forward middleware](https://github.com/cosmos/ibc-apps/tree/main/middleware/packet-forward-middleware),
+//! producing the first hop's `MsgTransfer` parameters and the nested `forward` memo for the others.
+use crate::route::{IbcRoute, RouteOptions};
+use crate::{ChainRegistry, Error};
+use serde::{Deserialize, Serialize};
+
+/// The default timeout of each forwarded hop.
+pub const DEFAULT_FORWARD_TIMEOUT: &str = "10m";
+
+/// The default number of retries of each forwarded hop.
+pub const DEFAULT_FORWARD_RETRIES: u8 = 2;
+
+/// Builds a [`ForwardTransfer`] from the registry's IBC channels, see
+/// [`ChainRegistry::forward_transfer`].
+///
+/// The intermediate receivers are derived from the final receiver by re-encoding it with each
+/// intermediate chain's `bech32_prefix`, so every chain on the route must derive addresses from
+/// the same key type.
+///
+/// ## Example
+///
+/// ```no_run
+/// use cosmos_chain_registry::ChainRegistry;
+///
+/// let registry = ChainRegistry::from_path("./chain-registry").unwrap();
+/// let transfer = registry
+///     .forward_transfer("juno", "cosmoshub", "cosmos1...")
+///     .timeout("30m")
+///     .build()
+///     .unwrap();
+///
+/// println!("{}", transfer.msg_transfer.memo);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ForwardTransferBuilder<'a> {
+    registry: &'a ChainRegistry,
+    source: String,
+    destination: String,
+    receiver: String,
+    route_options: RouteOptions,
+    timeout: String,
+    retries: u8,
+}
+
+impl<'a> ForwardTransferBuilder<'a> {
+    pub(crate) fn new(
+        registry: &'a ChainRegistry,
+        source: &str,
+        destination: &str,
+        receiver: &str,
+    ) -> Self {
+        Self {
+            registry,
+            source: source.to_string(),
+            destination: destination.to_string(),
+            receiver: receiver.to_string(),
+            route_options: RouteOptions::default(),
+            timeout: DEFAULT_FORWARD_TIMEOUT.to_string(),
+            retries: DEFAULT_FORWARD_RETRIES,
+        }
+    }
+
+    /// Sets which chains and channels the route may use.
+    pub fn route_options(mut self, route_options: RouteOptions) -> Self {
+        self.route_options = route_options;
+        self
+    }
+
+    /// Sets the timeout of each forwarded hop, as a Go duration string such as `10m`.
+    pub fn timeout(mut self, timeout: impl Into<String>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    /// Sets the number of retries of each forwarded hop.
+    pub fn retries(mut self, retries: u8) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Finds the shortest route and builds the transfer over it.
+    ///
+    /// Returns [`Error::InvalidAddress`] if the receiver is not a bech32 address with the
+    /// destination chain's `bech32_prefix`.
+    pub fn build(self) -> Result<ForwardTransfer, Error> {
+        let invalid = |reason: String| Error::InvalidAddress {
+            address: self.receiver.clone(),
+            reason,
+        };
+        let destination = self.registry.get_by_chain_name(&self.destination)?;
+        let (prefix, data, variant) =
+            bech32::decode(&self.receiver).map_err(|e| invalid(e.to_string()))?;
+        if prefix != destination.bech32_prefix {
+            return Err(invalid(format!(
+                "expected the {} prefix of {}, found {}",
+                destination.bech32_prefix, destination.chain_name, prefix
+            )));
+        }
+
+        let route =
+            self.registry
+                .find_ibc_route(&self.source, &self.destination, &self.route_options)?;
+        if route.is_empty() {
+            return Err(Error::NotFound(format!(
+                "IBC route from {} to {}",
+                self.source, self.destination
+            )));
+        }
+
+        // The receiver on every chain after the source, the final receiver last
+        let mut receivers = Vec::with_capacity(route.len());
+        for hop in &route.hops[1..] {
+            let chain_info = self.registry.get_by_chain_name(&hop.chain_name)?;
+            receivers.push(
+                bech32::encode(&chain_info.bech32_prefix, data.clone(), variant)
+                    .map_err(|e| invalid(e.to_string()))?,
+            );
+        }
+        receivers.push(self.receiver.clone());
+
+        // Nest the forwards from the last hop outwards
+        let mut memo = None;
+        for (hop, receiver) in route.hops.iter().zip(&receivers).skip(1).rev() {
+            memo = Some(ForwardMemo {
+                forward: Forward {
+                    receiver: receiver.clone(),
+                    port: hop.port_id.clone(),
+                    channel: hop.channel_id.clone(),
+                    timeout: self.timeout.clone(),
+                    retries: self.retries,
+                    next: memo.map(Box::new),
+                },
+            });
+        }
+
+        let first = &route.hops[0];
+        let msg_transfer = MsgTransferParams {
+            source_port: first.port_id.clone(),
+            source_channel: first.channel_id.clone(),
+            receiver: receivers[0].clone(),
+            memo: memo
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()
+                .expect("memo serializes to JSON")
+                .unwrap_or_default(),
+        };
+
+        Ok(ForwardTransfer {
+            msg_transfer,
+            memo,
+            route,
+        })
+    }
+}
+
+/// A multi-hop transfer: the `MsgTransfer` sent on the source chain and the route it takes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ForwardTransfer {
+    pub msg_transfer: MsgTransferParams,
+    /// The memo of the first hop, `None` for a direct transfer.
+    pub memo: Option<ForwardMemo>,
+    #[serde(skip)]
+    pub route: IbcRoute,
+}
+
+/// The route dependent parameters of the `MsgTransfer` sent on the source chain. The sender,
+/// token and timeout are left to the caller.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct MsgTransferParams {
+    pub source_port: String,
+    pub source_channel: String,
+    pub receiver: String,
+    /// The JSON encoded [`ForwardMemo`], empty for a direct transfer.
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    pub memo: String,
+}
+
+/// The memo instructing the packet forward middleware to forward a transfer.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct ForwardMemo {
+    pub forward: Forward,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct Forward {
+    pub receiver: String,
+    pub port: String,
+    pub channel: String,
+    pub timeout: String,
+    pub retries: u8,
+    /// The memo of the next hop, if the transfer is forwarded again.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub next: Option<Box<ForwardMemo>>,
+}
+
+/// Re-encodes a bech32 address with another human readable prefix, e.g. `cosmos1...` to
+/// `osmo1...`.
+pub fn convert_address(address: &str, prefix: &str) -> Result<String, Error> {
+    let invalid = |e: bech32::Error| Error::InvalidAddress {
+        address: address.to_string(),
+        reason: e.to_string(),
+    };
+
+    let (_, data, variant) = bech32::decode(address).map_err(invalid)?;
+    bech32::encode(prefix, data, variant).map_err(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_addresses_between_prefixes() {
+        use bech32::ToBase32;
+
+        let address =
+            bech32::encode("cosmos", [7u8; 20].to_base32(), bech32::Variant::Bech32).unwrap();
+        let converted = convert_address(&address, "osmo").unwrap();
+
+        assert!(converted.starts_with("osmo1"));
+        assert_eq!(convert_address(&converted, "cosmos").unwrap(), address);
+        assert!(matches!(
+            convert_address("not-an-address", "osmo"),
+            Err(Error::InvalidAddress { .. })
+        ));
+    }
+}
@@ -0,0 +1,267 @@
+//! Contains [`ChainQuery`], a composable filter over the fields of a [`ChainInfo`].
+use crate::chain::{ChainStatus, KeyAlgo, NetworkType};
+use crate::ChainInfo;
+use std::cmp::Ordering;
+
+/// A filter over [`ChainInfo`] fields, combinable with [`ChainQuery::and`], [`ChainQuery::or`]
+/// and `!`.
+///
+/// ## Example
+///
+/// ```rust
+/// use cosmos_chain_registry::chain::{ChainStatus, KeyAlgo, NetworkType};
+/// use cosmos_chain_registry::query::{ChainQuery, Comparison};
+///
+/// // All live mainnets with CosmWasm enabled, on Cosmos SDK 0.47 or later, using secp256k1
+/// let query = ChainQuery::network_type(NetworkType::Mainnet)
+///     .and(ChainQuery::status(ChainStatus::Live))
+///     .and(ChainQuery::cosmwasm_enabled(true))
+///     .and(ChainQuery::cosmos_sdk_version(Comparison::Ge, "0.47"))
+///     .and(ChainQuery::key_algo(KeyAlgo::Secp256k1));
+///
+/// // Everything but the Cosmos Hub
+/// let query = query.and(!ChainQuery::bech32_prefix("cosmos"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainQuery {
+    NetworkType(NetworkType),
+    Status(ChainStatus),
+    CosmwasmEnabled(bool),
+    CosmosSdkVersion(Comparison, String),
+    TendermintVersion(Comparison, String),
+    CosmwasmVersion(Comparison, String),
+    GitRepo(String),
+    KeyAlgo(KeyAlgo),
+    Slip44(u32),
+    Bech32Prefix(String),
+    FeeDenom(String),
+    StakingDenom(String),
+    /// Matches when every query matches. An empty list matches every chain.
+    And(Vec<ChainQuery>),
+    /// Matches when any query matches. An empty list matches no chain.
+    Or(Vec<ChainQuery>),
+    Not(Box<ChainQuery>),
+}
+
+/// How a chain's version is compared against the version given in a query.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl ChainQuery {
+    /// Matches chains with the given `network_type`, e.g. [`NetworkType::Mainnet`] or
+    /// `"mainnet"`.
+    pub fn network_type(network_type: impl Into<NetworkType>) -> Self {
+        Self::NetworkType(network_type.into())
+    }
+
+    /// Matches chains with the given `status`, e.g. [`ChainStatus::Live`] or `"live"`.
+    pub fn status(status: impl Into<ChainStatus>) -> Self {
+        Self::Status(status.into())
+    }
+
+    /// Matches chains with `codebase.cosmwasm_enabled` set to `enabled`.
+    pub fn cosmwasm_enabled(enabled: bool) -> Self {
+        Self::CosmwasmEnabled(enabled)
+    }
+
+    /// Matches chains whose `codebase.cosmos_sdk_version` compares to `version` as given.
+    pub fn cosmos_sdk_version(comparison: Comparison, version: impl Into<String>) -> Self {
+        Self::CosmosSdkVersion(comparison, version.into())
+    }
+
+    /// Matches chains whose `codebase.tendermint_version` compares to `version` as given.
+    pub fn tendermint_version(comparison: Comparison, version: impl Into<String>) -> Self {
+        Self::TendermintVersion(comparison, version.into())
+    }
+
+    /// Matches chains whose `codebase.cosmwasm_version` compares to `version` as given.
+    pub fn cosmwasm_version(comparison: Comparison, version: impl Into<String>) -> Self {
+        Self::CosmwasmVersion(comparison, version.into())
+    }
+
+    /// Matches chains with the given `codebase.git_repo`.
+    pub fn git_repo(git_repo: impl Into<String>) -> Self {
+        Self::GitRepo(git_repo.into())
+    }
+
+    /// Matches chains listing the given key algorithm in `key_algos`, e.g.
+    /// [`KeyAlgo::Secp256k1`] or `"secp256k1"`.
+    pub fn key_algo(key_algo: impl Into<KeyAlgo>) -> Self {
+        Self::KeyAlgo(key_algo.into())
+    }
+
+    /// Matches chains with the given `slip44` coin type.
+    pub fn slip44(slip44: u32) -> Self {
+        Self::Slip44(slip44)
+    }
+
+    /// Matches chains with the given `bech32_prefix`.
+    pub fn bech32_prefix(prefix: impl Into<String>) -> Self {
+        Self::Bech32Prefix(prefix.into())
+    }
+
+    /// Matches chains accepting the given denom as a fee token.
+    pub fn fee_denom(denom: impl Into<String>) -> Self {
+        Self::FeeDenom(denom.into())
+    }
+
+    /// Matches chains using the given denom as a staking token.
+    pub fn staking_denom(denom: impl Into<String>) -> Self {
+        Self::StakingDenom(denom.into())
+    }
+
+    /// Matches chains matched by both this query and `other`.
+    pub fn and(self, other: ChainQuery) -> Self {
+        match self {
+            Self::And(mut queries) => {
+                queries.push(other);
+                Self::And(queries)
+            }
+            query => Self::And(vec![query, other]),
+        }
+    }
+
+    /// Matches chains matched by either this query or `other`.
+    pub fn or(self, other: ChainQuery) -> Self {
+        match self {
+            Self::Or(mut queries) => {
+                queries.push(other);
+                Self::Or(queries)
+            }
+            query => Self::Or(vec![query, other]),
+        }
+    }
+
+    /// Returns `true` if the chain matches this query.
+    pub fn matches(&self, chain_info: &ChainInfo) -> bool {
+        let codebase = &chain_info.codebase;
+
+        match self {
+            Self::NetworkType(network_type) => &chain_info.network_type == network_type,
+            Self::Status(status) => &chain_info.status == status,
+            Self::CosmwasmEnabled(enabled) => codebase.cosmwasm_enabled == *enabled,
+            Self::CosmosSdkVersion(cmp, version) => {
+                compare_versions(&codebase.cosmos_sdk_version, *cmp, version)
+            }
+            Self::TendermintVersion(cmp, version) => {
+                compare_versions(&codebase.tendermint_version, *cmp, version)
+            }
+            Self::CosmwasmVersion(cmp, version) => {
+                compare_versions(&codebase.cosmwasm_version, *cmp, version)
+            }
+            Self::GitRepo(git_repo) => &codebase.git_repo == git_repo,
+            Self::KeyAlgo(key_algo) => chain_info.key_algos.contains(key_algo),
+            Self::Slip44(slip44) => chain_info.slip44 == *slip44,
+            Self::Bech32Prefix(prefix) => &chain_info.bech32_prefix == prefix,
+            Self::FeeDenom(denom) => chain_info
+                .fees
+                .fee_tokens
+                .iter()
+                .any(|token| &token.denom == denom),
+            Self::StakingDenom(denom) => chain_info
+                .staking
+                .staking_tokens
+                .iter()
+                .any(|token| &token.denom == denom),
+            Self::And(queries) => queries.iter().all(|query| query.matches(chain_info)),
+            Self::Or(queries) => queries.iter().any(|query| query.matches(chain_info)),
+            Self::Not(query) => !query.matches(chain_info),
+        }
+    }
+}
+
+impl std::ops::Not for ChainQuery {
+    type Output = ChainQuery;
+
+    fn not(self) -> Self::Output {
+        match self {
+            Self::Not(query) => *query,
+            query => Self::Not(Box::new(query)),
+        }
+    }
+}
+
+/// Compares a chain's version string against a query version. Versions are compared by their
+/// numeric components, so `v0.47.5`, `0.47.5-ics` and `0.47.5` are equal and missing trailing
+/// components count as zero. Chains without a parsable version never match.
+fn compare_versions(chain_version: &str, comparison: Comparison, version: &str) -> bool {
+    let (Some(chain_version), Some(version)) =
+        (parse_version(chain_version), parse_version(version))
+    else {
+        return false;
+    };
+
+    let len = chain_version.len().max(version.len());
+    let pad = |v: Vec<u64>| v.into_iter().chain(std::iter::repeat(0)).take(len);
+    let ordering = pad(chain_version).cmp(pad(version));
+
+    match comparison {
+        Comparison::Lt => ordering == Ordering::Less,
+        Comparison::Le => ordering != Ordering::Greater,
+        Comparison::Eq => ordering == Ordering::Equal,
+        Comparison::Ge => ordering != Ordering::Less,
+        Comparison::Gt => ordering == Ordering::Greater,
+    }
+}
+
+/// Parses the leading numeric components of a version, e.g. `v0.45.16-ics` into `[0, 45, 16]`.
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .next()
+        .unwrap_or_default();
+
+    let components: Vec<u64> = core
+        .split('.')
+        .map_while(|component| component.parse().ok())
+        .collect();
+
+    (!components.is_empty()).then_some(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions_by_numeric_components() {
+        assert!(compare_versions("v0.47.5", Comparison::Ge, "0.47"));
+        assert!(compare_versions("0.45.16-ics", Comparison::Lt, "0.47"));
+        assert!(compare_versions("0.47", Comparison::Eq, "v0.47.0"));
+        assert!(compare_versions("0.50.1", Comparison::Gt, "0.47.99"));
+        assert!(!compare_versions("", Comparison::Le, "0.47"));
+        assert!(!compare_versions("unknown", Comparison::Ge, "0.1"));
+    }
+
+    #[test]
+    fn combines_queries() {
+        let mut chain_info = ChainInfo {
+            network_type: NetworkType::Mainnet,
+            bech32_prefix: "juno".to_string(),
+            key_algos: vec![KeyAlgo::Secp256k1],
+            ..Default::default()
+        };
+        chain_info.codebase.cosmwasm_enabled = true;
+        chain_info.codebase.cosmos_sdk_version = "v0.47.3".to_string();
+
+        let query = ChainQuery::network_type("mainnet")
+            .and(ChainQuery::cosmwasm_enabled(true))
+            .and(ChainQuery::cosmos_sdk_version(Comparison::Ge, "0.47"))
+            .and(ChainQuery::key_algo("secp256k1"));
+        assert!(query.matches(&chain_info));
+        assert!(!(!query.clone()).matches(&chain_info));
+
+        let query = query.and(ChainQuery::slip44(60).or(ChainQuery::bech32_prefix("osmo")));
+        assert!(!query.matches(&chain_info));
+
+        assert!(ChainQuery::And(vec![]).matches(&chain_info));
+        assert!(!ChainQuery::Or(vec![]).matches(&chain_info));
+    }
+}
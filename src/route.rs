@@ -0,0 +1,327 @@
+//! Contains IBC route finding over the transfer channels described in the registry's `_IBC` directory.
+use crate::ibc::{ChannelInfo, IbcData};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Selects which channels and chains an IBC route may use.
+///
+/// By default routes use every transfer channel that is not tagged as `killed`, through any
+/// chain, with at most 4 hops, and at most 100 routes are listed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouteOptions {
+    /// Chain names the route must not pass through.
+    pub exclude: HashSet<String>,
+    /// Only use channels tagged as `live` in the registry.
+    pub require_live: bool,
+    /// The maximum number of hops of a route.
+    pub max_hops: usize,
+    /// The maximum number of routes listed by
+    /// [`ChainRegistry::find_ibc_routes`](crate::ChainRegistry::find_ibc_routes).
+    pub max_routes: usize,
+}
+
+impl Default for RouteOptions {
+    fn default() -> Self {
+        Self {
+            exclude: HashSet::new(),
+            require_live: false,
+            max_hops: 4,
+            max_routes: 100,
+        }
+    }
+}
+
+impl RouteOptions {
+    /// Excludes a chain from the route.
+    pub fn exclude(mut self, chain_name: impl Into<String>) -> Self {
+        self.exclude.insert(chain_name.into());
+        self
+    }
+
+    /// Sets whether only channels tagged as `live` are used.
+    pub fn require_live(mut self, require_live: bool) -> Self {
+        self.require_live = require_live;
+        self
+    }
+
+    /// Sets the maximum number of hops of a route.
+    pub fn max_hops(mut self, max_hops: usize) -> Self {
+        self.max_hops = max_hops;
+        self
+    }
+
+    /// Sets the maximum number of routes listed.
+    pub fn max_routes(mut self, max_routes: usize) -> Self {
+        self.max_routes = max_routes;
+        self
+    }
+
+    fn allows(&self, channel: &ChannelInfo) -> bool {
+        channel.is_transfer()
+            && if self.require_live {
+                channel.is_live()
+            } else {
+                !channel.is_killed()
+            }
+    }
+}
+
+/// A path of IBC transfer channels between two chains.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IbcRoute {
+    /// The channels of the route in order, each seen from the chain sending over it.
+    pub hops: Vec<ChannelInfo>,
+}
+
+impl IbcRoute {
+    /// The number of hops of the route.
+    pub fn len(&self) -> usize {
+        self.hops.len()
+    }
+
+    /// Returns `true` if the route has no hops, i.e. it starts and ends on the same chain.
+    pub fn is_empty(&self) -> bool {
+        self.hops.is_empty()
+    }
+
+    /// The chain names along the route, including the source and the destination.
+    pub fn chains(&self) -> Vec<&str> {
+        let mut chains: Vec<&str> = self
+            .hops
+            .iter()
+            .map(|hop| hop.chain_name.as_str())
+            .collect();
+        chains.extend(
+            self.hops
+                .last()
+                .map(|hop| hop.counterparty_chain_name.as_str()),
+        );
+        chains
+    }
+}
+
+/// The transfer channels of every chain, keyed by the chain sending over them. Preferred
+/// channels are sorted first so they win ties between routes of the same length.
+pub(crate) struct ChannelGraph {
+    edges: HashMap<String, Vec<ChannelInfo>>,
+}
+
+impl ChannelGraph {
+    pub(crate) fn new<'a>(
+        ibc_data: impl IntoIterator<Item = &'a IbcData>,
+        options: &RouteOptions,
+    ) -> Self {
+        let mut edges: HashMap<String, Vec<ChannelInfo>> = HashMap::new();
+
+        for ibc_data in ibc_data {
+            for chain_name in [&ibc_data.chain_1.chain_name, &ibc_data.chain_2.chain_name] {
+                if options.exclude.contains(chain_name) {
+                    continue;
+                }
+                let channels = ibc_data.channels_from(chain_name).unwrap_or_default();
+                edges
+                    .entry(chain_name.clone())
+                    .or_default()
+                    .extend(channels.into_iter().filter(|channel| {
+                        options.allows(channel)
+                            && !options.exclude.contains(&channel.counterparty_chain_name)
+                    }));
+            }
+        }
+
+        for channels in edges.values_mut() {
+            channels.sort_by_key(|channel| !channel.preferred);
+        }
+
+        Self { edges }
+    }
+
+    fn channels(&self, chain_name: &str) -> &[ChannelInfo] {
+        self.edges.get(chain_name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// The route with the fewest hops from `from` to `to`, found with a breadth first search.
+    pub(crate) fn shortest(&self, from: &str, to: &str, max_hops: usize) -> Option<IbcRoute> {
+        let mut previous: HashMap<&str, &ChannelInfo> = HashMap::new();
+        let mut visited = HashSet::from([from]);
+        let mut queue = VecDeque::from([(from, 0)]);
+
+        while let Some((chain_name, hops)) = queue.pop_front() {
+            if chain_name == to {
+                let mut route = Vec::new();
+                let mut current = to;
+                while let Some(channel) = previous.get(current) {
+                    route.push((*channel).clone());
+                    current = &channel.chain_name;
+                }
+                route.reverse();
+                return Some(IbcRoute { hops: route });
+            }
+            if hops == max_hops {
+                continue;
+            }
+
+            for channel in self.channels(chain_name) {
+                let next = channel.counterparty_chain_name.as_str();
+                if visited.insert(next) {
+                    previous.insert(next, channel);
+                    queue.push_back((next, hops + 1));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// The number of hops from every chain to `to`, for chains reaching it within `max_hops`.
+    fn distances_to<'a>(&'a self, to: &'a str, max_hops: usize) -> HashMap<&'a str, usize> {
+        let mut senders: HashMap<&str, Vec<&str>> = HashMap::new();
+        for channels in self.edges.values() {
+            for channel in channels {
+                senders
+                    .entry(&channel.counterparty_chain_name)
+                    .or_default()
+                    .push(&channel.chain_name);
+            }
+        }
+
+        let mut distances = HashMap::from([(to, 0)]);
+        let mut queue = VecDeque::from([(to, 0)]);
+        while let Some((chain_name, hops)) = queue.pop_front() {
+            if hops == max_hops {
+                continue;
+            }
+            for sender in senders.get(chain_name).into_iter().flatten() {
+                if !distances.contains_key(sender) {
+                    distances.insert(*sender, hops + 1);
+                    queue.push_back((*sender, hops + 1));
+                }
+            }
+        }
+        distances
+    }
+
+    /// Every route from `from` to `to` that visits no chain twice, shortest first, stopping
+    /// after `max_routes`. Routes are searched one length at a time, only through chains that
+    /// can still reach `to` within the remaining hops, so the search stays bounded on densely
+    /// connected hubs.
+    pub(crate) fn all(
+        &self,
+        from: &str,
+        to: &str,
+        max_hops: usize,
+        max_routes: usize,
+    ) -> Vec<IbcRoute> {
+        let distances = self.distances_to(to, max_hops);
+        let shortest = match distances.get(from) {
+            Some(shortest) => *shortest,
+            None => return Vec::new(),
+        };
+
+        let mut walk = Walk {
+            graph: self,
+            to,
+            distances,
+            path: Vec::new(),
+            visited: HashSet::from([from]),
+            routes: Vec::new(),
+            max_routes,
+        };
+        for hops in shortest..=max_hops {
+            if walk.routes.len() >= max_routes {
+                break;
+            }
+            walk.walk(from, hops);
+        }
+        walk.routes
+    }
+}
+
+/// The state of the depth first search of [`ChannelGraph::all`]. The path holds references
+/// into the graph, channels are only cloned for the routes found.
+struct Walk<'a> {
+    graph: &'a ChannelGraph,
+    to: &'a str,
+    distances: HashMap<&'a str, usize>,
+    path: Vec<&'a ChannelInfo>,
+    visited: HashSet<&'a str>,
+    routes: Vec<IbcRoute>,
+    max_routes: usize,
+}
+
+impl<'a> Walk<'a> {
+    /// Collects the routes of exactly `hops` hops extending the current path from `chain_name`.
+    fn walk(&mut self, chain_name: &'a str, hops: usize) {
+        if self.routes.len() >= self.max_routes {
+            return;
+        }
+        if chain_name == self.to {
+            if self.path.len() == hops {
+                self.routes.push(IbcRoute {
+                    hops: self.path.iter().map(|channel| (*channel).clone()).collect(),
+                });
+            }
+            return;
+        }
+
+        let remaining = hops - self.path.len();
+        for channel in self.graph.channels(chain_name) {
+            let next = channel.counterparty_chain_name.as_str();
+            let reachable = self
+                .distances
+                .get(next)
+                .is_some_and(|distance| *distance < remaining);
+            if reachable && self.visited.insert(next) {
+                self.path.push(channel);
+                self.walk(next, hops);
+                self.path.pop();
+                self.visited.remove(next);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A graph where every chain has a transfer channel to every other chain.
+    fn complete_graph(chains: usize) -> ChannelGraph {
+        let names: Vec<String> = (0..chains).map(|i| format!("chain{}", i)).collect();
+        let edges = names
+            .iter()
+            .map(|from| {
+                let channels = names
+                    .iter()
+                    .filter(|to| *to != from)
+                    .map(|to| ChannelInfo {
+                        chain_name: from.clone(),
+                        counterparty_chain_name: to.clone(),
+                        ..Default::default()
+                    })
+                    .collect();
+                (from.clone(), channels)
+            })
+            .collect();
+        ChannelGraph { edges }
+    }
+
+    #[test]
+    fn limits_the_routes_of_dense_graphs() {
+        // 150 chains have millions of routes of up to 4 hops between any two of them
+        let graph = complete_graph(150);
+        let routes = graph.all("chain0", "chain1", 4, 200);
+        assert_eq!(routes.len(), 200);
+        assert_eq!(routes[0].chains(), ["chain0", "chain1"]);
+        // Every 2 hop route through the 148 other chains comes before the 3 hop routes
+        assert!(routes[1..149].iter().all(|route| route.len() == 2));
+        assert!(routes[149..].iter().all(|route| route.len() == 3));
+
+        let graph = complete_graph(5);
+        let routes = graph.all("chain0", "chain1", 4, usize::MAX);
+        // 1 direct route, then 3, 3 * 2 and 3 * 2 * 1 routes through the other chains
+        assert_eq!(routes.len(), 1 + 3 + 6 + 6);
+        assert!(routes.windows(2).all(|pair| pair[0].len() <= pair[1].len()));
+        assert!(graph.all("chain0", "chain9", 4, 10).is_empty());
+        assert_eq!(graph.all("chain0", "chain0", 4, 10), [IbcRoute::default()]);
+    }
+}
@@ -0,0 +1,283 @@
+//! Contains the selection of an endpoint out of a chain's `apis`, failing over to the next
+//! endpoint when callers report failures.
+use crate::chain::{ApiKind, Apis, Endpoint};
+use crate::ChainInfo;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a failed endpoint is skipped by default.
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// How an [`EndpointSelector`] picks among the endpoints that have not failed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum SelectionStrategy {
+    /// The first endpoint in registry order.
+    #[default]
+    FirstHealthy,
+    /// The endpoint with the lowest reported latency, endpoints without a latency last.
+    LowestLatency,
+    /// Each endpoint in turn.
+    RoundRobin,
+    /// The first endpoint of the earliest listed provider, ignoring case, falling back to
+    /// registry order.
+    ProviderPreferred(Vec<String>),
+}
+
+/// Hands out endpoints of one kind according to a [`SelectionStrategy`].
+///
+/// Endpoints reported as failed are skipped until their cooldown has passed. The selector
+/// can be shared between threads.
+///
+/// ## Example
+///
+/// ```no_run
+/// use cosmos_chain_registry::chain::ApiKind;
+/// use cosmos_chain_registry::selector::{EndpointSelector, SelectionStrategy};
+/// use cosmos_chain_registry::ChainRegistry;
+///
+/// let registry = ChainRegistry::from_path("./chain-registry").unwrap();
+/// let info = registry.get_by_chain_id("juno-1").unwrap();
+/// let selector = EndpointSelector::from_chain_info(&info, ApiKind::Rpc)
+///     .strategy(SelectionStrategy::RoundRobin);
+///
+/// let endpoint = selector.select().unwrap();
+/// // The request failed, the next call hands out another endpoint
+/// selector.report_failure(&endpoint.address);
+/// ```
+#[derive(Debug)]
+pub struct EndpointSelector {
+    strategy: SelectionStrategy,
+    cooldown: Duration,
+    state: Mutex<SelectorState>,
+}
+
+#[derive(Debug)]
+struct SelectorState {
+    endpoints: Vec<EndpointState>,
+    /// The index the next round robin selection starts from.
+    next: usize,
+}
+
+#[derive(Debug)]
+struct EndpointState {
+    endpoint: Endpoint,
+    failed_at: Option<Instant>,
+    latency: Option<Duration>,
+}
+
+impl EndpointSelector {
+    /// Creates a selector over the given endpoints, using [`SelectionStrategy::FirstHealthy`].
+    pub fn new(endpoints: Vec<Endpoint>) -> Self {
+        let endpoints = endpoints
+            .into_iter()
+            .map(|endpoint| EndpointState {
+                endpoint,
+                failed_at: None,
+                latency: None,
+            })
+            .collect();
+
+        Self {
+            strategy: SelectionStrategy::default(),
+            cooldown: DEFAULT_COOLDOWN,
+            state: Mutex::new(SelectorState { endpoints, next: 0 }),
+        }
+    }
+
+    /// Creates a selector over the endpoints of the given kind.
+    pub fn from_apis(apis: &Apis, kind: ApiKind) -> Self {
+        Self::new(apis.endpoints(kind))
+    }
+
+    /// Creates a selector over the chain's endpoints of the given kind.
+    pub fn from_chain_info(chain_info: &ChainInfo, kind: ApiKind) -> Self {
+        Self::from_apis(&chain_info.apis, kind)
+    }
+
+    /// Sets how endpoints are picked.
+    pub fn strategy(mut self, strategy: SelectionStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets how long a failed endpoint is skipped.
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Picks an endpoint. Returns `None` if there are no endpoints or all of them are cooling
+    /// down after a failure.
+    pub fn select(&self) -> Option<Endpoint> {
+        let mut state = self.lock();
+        let now = Instant::now();
+        for endpoint in &mut state.endpoints {
+            if endpoint
+                .failed_at
+                .is_some_and(|failed_at| now.duration_since(failed_at) >= self.cooldown)
+            {
+                endpoint.failed_at = None;
+            }
+        }
+
+        let available: Vec<usize> = (0..state.endpoints.len())
+            .filter(|&i| state.endpoints[i].failed_at.is_none())
+            .collect();
+
+        let selected = match &self.strategy {
+            SelectionStrategy::FirstHealthy => available.first().copied(),
+            SelectionStrategy::LowestLatency => available.iter().copied().min_by_key(|&i| {
+                (
+                    state.endpoints[i].latency.is_none(),
+                    state.endpoints[i].latency,
+                )
+            }),
+            SelectionStrategy::RoundRobin => {
+                let len = state.endpoints.len();
+                let selected = (0..len)
+                    .map(|offset| (state.next + offset) % len)
+                    .find(|i| available.contains(i));
+                if let Some(i) = selected {
+                    state.next = i + 1;
+                }
+                selected
+            }
+            SelectionStrategy::ProviderPreferred(providers) => providers
+                .iter()
+                .find_map(|provider| {
+                    available.iter().copied().find(|&i| {
+                        state.endpoints[i]
+                            .endpoint
+                            .provider
+                            .as_deref()
+                            .is_some_and(|p| p.eq_ignore_ascii_case(provider))
+                    })
+                })
+                .or_else(|| available.first().copied()),
+        };
+
+        selected.map(|i| state.endpoints[i].endpoint.clone())
+    }
+
+    /// Marks the endpoint with the given address as failed, skipping it until the cooldown
+    /// has passed.
+    pub fn report_failure(&self, address: &str) {
+        let now = Instant::now();
+        for endpoint in self.lock().endpoints.iter_mut() {
+            if endpoint.endpoint.address == address {
+                endpoint.failed_at = Some(now);
+            }
+        }
+    }
+
+    /// Records a successful request to the endpoint with the given address, re-admitting it
+    /// if it had failed. The latency is used by [`SelectionStrategy::LowestLatency`].
+    pub fn report_success(&self, address: &str, latency: Duration) {
+        for endpoint in self.lock().endpoints.iter_mut() {
+            if endpoint.endpoint.address == address {
+                endpoint.failed_at = None;
+                endpoint.latency = Some(latency);
+            }
+        }
+    }
+
+    /// Records the outcome of a [`HealthReport`](crate::health::HealthReport): healthy
+    /// endpoints have their latency updated, the others are marked as failed.
+    #[cfg(feature = "health")]
+    pub fn report_health(&self, report: &crate::health::HealthReport) {
+        for health in &report.endpoints {
+            match health.latency {
+                Some(latency) if health.is_healthy() => {
+                    self.report_success(&health.endpoint.address, latency)
+                }
+                _ => self.report_failure(&health.endpoint.address),
+            }
+        }
+    }
+
+    /// The endpoints that are not cooling down after a failure, in registry order.
+    pub fn available(&self) -> Vec<Endpoint> {
+        let now = Instant::now();
+        self.lock()
+            .endpoints
+            .iter()
+            .filter(|endpoint| {
+                endpoint
+                    .failed_at
+                    .is_none_or(|failed_at| now.duration_since(failed_at) >= self.cooldown)
+            })
+            .map(|endpoint| endpoint.endpoint.clone())
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SelectorState> {
+        // The state stays consistent even if a holder panicked
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoints() -> Vec<Endpoint> {
+        [("a", "polkachu"), ("b", "lavender.five"), ("c", "Notional")]
+            .into_iter()
+            .map(|(address, provider)| Endpoint {
+                kind: ApiKind::Rpc,
+                address: address.to_string(),
+                provider: Some(provider.to_string()),
+            })
+            .collect()
+    }
+
+    fn select(selector: &EndpointSelector) -> String {
+        selector.select().unwrap().address
+    }
+
+    #[test]
+    fn fails_over_and_readmits_after_cooldown() {
+        let selector = EndpointSelector::new(endpoints());
+        assert_eq!(select(&selector), "a");
+
+        selector.report_failure("a");
+        assert_eq!(select(&selector), "b");
+        selector.report_failure("b");
+        selector.report_failure("c");
+        assert_eq!(selector.select(), None);
+        assert!(selector.available().is_empty());
+
+        selector.report_success("b", Duration::from_millis(10));
+        assert_eq!(select(&selector), "b");
+
+        let selector = EndpointSelector::new(endpoints()).cooldown(Duration::ZERO);
+        selector.report_failure("a");
+        assert_eq!(select(&selector), "a");
+    }
+
+    #[test]
+    fn selects_by_strategy() {
+        let selector = EndpointSelector::new(endpoints()).strategy(SelectionStrategy::RoundRobin);
+        let picked: Vec<String> = (0..4).map(|_| select(&selector)).collect();
+        assert_eq!(picked, ["a", "b", "c", "a"]);
+        selector.report_failure("b");
+        assert_eq!(select(&selector), "c");
+
+        let selector =
+            EndpointSelector::new(endpoints()).strategy(SelectionStrategy::LowestLatency);
+        selector.report_success("c", Duration::from_millis(20));
+        selector.report_success("b", Duration::from_millis(50));
+        assert_eq!(select(&selector), "c");
+        selector.report_failure("c");
+        assert_eq!(select(&selector), "b");
+
+        let selector = EndpointSelector::new(endpoints()).strategy(
+            SelectionStrategy::ProviderPreferred(vec!["notional".into(), "polkachu".into()]),
+        );
+        assert_eq!(select(&selector), "c");
+        selector.report_failure("c");
+        assert_eq!(select(&selector), "a");
+        selector.report_failure("a");
+        assert_eq!(select(&selector), "b");
+    }
+}